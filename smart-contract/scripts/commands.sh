@@ -9,6 +9,10 @@ concordium-client config account import ./path/to/oracle.export --name oracle
 # 1) Build WASM + schema
 cargo concordium build --out dist/paylog.wasm.v1 --schema-out dist/paylog.schema.bin
 
+# 1b) Optional: test the no_std build (concordium-std requires nightly here,
+#     plus `rustup +nightly component add rust-src`)
+PAYLOG_NO_STD=1 cargo +nightly test --test paylog
+
 # 2) Deploy module
 concordium-client module deploy dist/paylog.wasm.v1 \
   --sender client \
@@ -11,6 +11,19 @@
 //!
 //! This contract stores the attestation trail (work_hash, payment hash, timestamps)
 //! and emits events for indexers / UI. It does NOT move tokens.
+//!
+//! `no_std`: all text fields (memos, labels, currency symbols, ids) are
+//! `concordium_std` `String`s, logged via `Serial` as raw bytes; nothing here
+//! uses `std` formatting. Amounts stay raw `u128` minor units in events and are
+//! scaled by the UI. Note: `concordium-std` itself needs nightly without `std`;
+//! `PAYLOG_NO_STD=1 cargo +nightly test --test paylog` runs the integration
+//! tests (memo-bearing `confirmPayment` included) against that build.
+//!
+//! Ordering: entrypoints mutate state first and log events after. This is safe
+//! because a receive call that returns `Err` is rolled back as a whole (state
+//! and logs), and every `logger.log` error propagates with `?` (see
+//! `From<LogError>`), batches included. No entrypoint calls another contract,
+//! so there is no reentrancy window in between the mutation and the log.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -19,6 +32,9 @@ use concordium_std::*;
 // ---- Aliases for clarity -----------------------------------------------------
 
 /// Milestone identifiers are small integers for easy indexing.
+/// They are `StateMap` keys, never cast to `usize` indices, so any `u32`
+/// (including `u32::MAX`) is safe to pass: unknown ids are simply absent and
+/// surface as `InvalidMilestone` (or `None` in views).
 type MilestoneId = u32;
 
 /// 32-byte SHA-256 (work proof digest).
@@ -27,6 +43,9 @@ pub type Hash32 = [u8; 32];
 /// 32-byte transaction hash (e.g., PLT transfer hash or registerData hash).
 pub type TxHash = [u8; 32];
 
+/// 16-byte client-chosen retry key (e.g., a UUID) for `confirmPayment`.
+pub type IdempotencyKey = [u8; 16];
+
 // ---- Init (constructor) parameters -------------------------------------------
 
 /// Parameters passed at contract initialization time.
@@ -38,12 +57,53 @@ pub struct InitParams {
     pub client: AccountAddress,
     /// Freelancer's account (payee).
     pub freelancer: AccountAddress,
-    /// Oracle's account (AI agent / verifier).
-    pub oracle: AccountAddress,
+    /// Oracle accounts (AI agents / verifiers); any of them may act as oracle.
+    /// At most `MAX_ORACLES` (8).
+    pub oracles: Vec<AccountAddress>,
+    /// Distinct oracle approvals of the same work hash needed to request release.
+    pub threshold: u32,
     /// Milestone amounts in *minor* units of the PLT (e.g., 6 decimals -> 100.00 = 100_000_000).
     pub amounts: Vec<u128>,
     /// For display purposes only (contract stores raw minor units).
     pub plt_decimals: u8,
+    /// Optional per-milestone verification deadlines (must match `amounts` length).
+    pub deadlines_ms: Option<Vec<Timestamp>>,
+    /// If true, `confirmPayment` requires the freelancer's acknowledgement first.
+    pub require_acknowledge: bool,
+    /// Max allowed |paid - amount| difference in minor units (None -> 0).
+    pub amount_tolerance_minor: Option<u128>,
+    /// If true, milestone N can only be requested once milestone N-1 is released
+    /// (or cancelled).
+    pub sequential: bool,
+    /// Optional max milliseconds between request and confirmation.
+    pub confirm_window_ms: Option<u64>,
+    /// Optional per-milestone external reference ids (must match `amounts` length;
+    /// empty strings mean "no reference").
+    pub ref_ids: Option<Vec<String>>,
+    /// Optional per-milestone category labels, e.g. "design" (must match
+    /// `amounts` length; empty strings mean "no label").
+    pub labels: Option<Vec<String>>,
+    /// Optional display label for amounts (e.g. "EUROe"); informational only.
+    pub currency_symbol: Option<String>,
+    /// Optional backup account allowed to pause, cancel and reset in emergencies.
+    pub admin: Option<AccountAddress>,
+    /// Optional per-milestone verifier (must match `amounts` length; each entry
+    /// must be one of `oracles`). Replaces the quorum for that milestone.
+    pub oracle_per_milestone: Option<Vec<AccountAddress>>,
+    /// If false, `confirmPayment` on an unrequested milestone implicitly
+    /// requests it first (with the supplied `work_hash`); true keeps the
+    /// oracle-first ordering and rejects with `NotRequested`.
+    pub strict_ordering: bool,
+    /// Optional per-milestone dependencies (must match `amounts` length): ids
+    /// that must be released (or cancelled) before that milestone can be
+    /// requested. Must be in range and acyclic.
+    pub depends_on: Option<Vec<Vec<MilestoneId>>>,
+    /// If true, a fully paid milestone is only released once an oracle has
+    /// also called `oracleCosign` for it (either order).
+    pub require_oracle_cosign: bool,
+    /// Optional per-milestone display decimals for mixed-token projects (must
+    /// match `amounts` length; each <= 38). Defaults to `plt_decimals`.
+    pub decimals_per_milestone: Option<Vec<u8>>,
 }
 
 // ---- Persistent state --------------------------------------------------------
@@ -58,24 +118,248 @@ pub struct Milestone {
     /// Has the client confirmed payment (i.e., milestone released)?
     pub released: bool,
     /// Optional SHA-256 work hash provided by oracle.
+    /// Set by the first oracle approval; final once `requested` is true.
     pub work_hash: Option<Hash32>,
-    /// Optional PLT transfer tx hash provided by client at confirm.
-    pub plt_tx_hash: Option<TxHash>,
+    /// Oracles that approved `work_hash` so far (distinct, in approval order).
+    pub oracle_approvals: Vec<AccountAddress>,
+    /// PLT transfer tx hashes provided by client at confirm, in payment order.
+    pub plt_tx_hashes: Vec<TxHash>,
+    /// Sum of payments confirmed so far (released once it equals `amount_minor`).
+    pub paid_so_far_minor: u128,
     /// Timestamp at `requestRelease` (block time).
+    /// NOTE: `ChainMetadata` only exposes the block slot time, not the block
+    /// height, so heights are not recorded. Correlate with blocks off-chain via
+    /// the transaction that emitted the corresponding event.
     pub requested_at_ms: Option<Timestamp>,
     /// Timestamp at `confirmPayment` (block time).
     pub attested_at_ms: Option<Timestamp>,
+    /// Has the milestone been cancelled (no further request/confirm)?
+    pub cancelled: bool,
+    /// Latest block time at which `requestRelease` is still accepted.
+    pub deadline_ms: Option<Timestamp>,
+    /// Is there an open dispute (blocks `confirmPayment` until resolved)?
+    pub disputed: bool,
+    /// PLT token id the milestone was paid in, provided by client at confirm.
+    pub plt_token_id: Option<String>,
+    /// Optional client note attached at confirm (e.g. invoice number).
+    pub memo: Option<String>,
+    /// Optional `sha256(work_hash || salt)` pre-committed by the oracle.
+    pub work_commitment: Option<Hash32>,
+    /// Has the freelancer acknowledged the requested milestone as complete?
+    pub acknowledged: bool,
+    /// Has the client recorded a PLT refund after release?
+    pub refunded: bool,
+    /// PLT refund tx hash provided by client at `recordRefund`.
+    pub refund_tx_hash: Option<TxHash>,
+    /// Earmarking PLT tx hash provided by client at `recordDeposit`.
+    pub deposit_tx_hash: Option<TxHash>,
+    /// All artifact hashes approved with the request; `work_hash` is the first.
+    pub work_hashes: Vec<Hash32>,
+    /// Off-chain reference (e.g. invoice UUID) set by the client.
+    pub ref_id: Option<String>,
+    /// Sender of the latest PLT payment, as attested at confirm.
+    pub payer: Option<AccountAddress>,
+    /// Receiver of the latest PLT payment, as attested at confirm.
+    pub payee: Option<AccountAddress>,
+    /// If set, only this oracle may approve, and its approval alone suffices.
+    pub assigned_oracle: Option<AccountAddress>,
+    /// Category label (e.g. "design", "dev", "QA") set by the client.
+    pub label: Option<String>,
+    /// Freelancer's ed25519 receipt over the final PLT tx hash.
+    pub receipt_sig: Option<[u8; 64]>,
+    /// Milestones that must be settled (released or cancelled) before this one
+    /// can be requested.
+    pub depends_on: Vec<MilestoneId>,
+    /// Frozen by the client/admin: no request or confirm until unlocked.
+    pub locked: bool,
+    /// Client's confirms cover the full amount (co-signed projects only wait
+    /// on `oracle_cosigned` after this).
+    pub client_confirmed: bool,
+    /// An oracle called `oracleCosign` for the current request.
+    pub oracle_cosigned: bool,
+    /// Display decimals of this milestone's token (project `plt_decimals`
+    /// unless overridden at init).
+    pub plt_decimals: u8,
+    /// Bumped by every reopen/reset; part of relayed oracle signatures.
+    pub request_nonce: u32,
+    /// Idempotency keys of confirms applied to this milestone (mirrors
+    /// `State::idempotency_keys`, so a reset drops them without a full scan).
+    pub idempotency_keys: Vec<IdempotencyKey>,
+}
+
+impl Milestone {
+    /// Fresh, un-requested milestone for the given amount.
+    fn new(amount_minor: u128, deadline_ms: Option<Timestamp>, plt_decimals: u8) -> Self {
+        Milestone {
+            amount_minor,
+            requested: false,
+            released: false,
+            work_hash: None,
+            oracle_approvals: Vec::new(),
+            plt_tx_hashes: Vec::new(),
+            paid_so_far_minor: 0,
+            requested_at_ms: None,
+            attested_at_ms: None,
+            cancelled: false,
+            deadline_ms,
+            disputed: false,
+            plt_token_id: None,
+            memo: None,
+            work_commitment: None,
+            acknowledged: false,
+            refunded: false,
+            refund_tx_hash: None,
+            deposit_tx_hash: None,
+            work_hashes: Vec::new(),
+            ref_id: None,
+            payer: None,
+            payee: None,
+            assigned_oracle: None,
+            label: None,
+            receipt_sig: None,
+            depends_on: Vec::new(),
+            locked: false,
+            client_confirmed: false,
+            oracle_cosigned: false,
+            plt_decimals,
+            request_nonce: 0,
+            idempotency_keys: Vec::new(),
+        }
+    }
+
+    /// Does this milestone currently hold `tx_hash` (payment, refund or deposit)?
+    fn holds_tx_hash(&self, tx_hash: &TxHash) -> bool {
+        self.plt_tx_hashes.contains(tx_hash)
+            || self.refund_tx_hash.as_ref() == Some(tx_hash)
+            || self.deposit_tx_hash.as_ref() == Some(tx_hash)
+    }
+
+    /// Released or cancelled: nothing can block on this milestone any more.
+    /// Gates (`sequential`, `depends_on`) treat cancelled as settled, otherwise
+    /// one cancelled milestone would block its successors forever.
+    fn settled(&self) -> bool {
+        self.released || self.cancelled
+    }
+
+    /// Status bucket for this milestone; cancelled unpaid milestones have none.
+    fn status(&self) -> Option<MilestoneStatus> {
+        if self.released {
+            Some(MilestoneStatus::Released)
+        } else if self.cancelled {
+            None
+        } else if self.requested {
+            Some(MilestoneStatus::Requested)
+        } else {
+            Some(MilestoneStatus::Pending)
+        }
+    }
 }
 
 /// Contract storage (single-project instance).
-#[derive(Serial, Deserial, SchemaType, Clone)]
-pub struct State {
-    pub project_id: String,         // for convenience in events/UI
-    pub client: AccountAddress,     // payer (sole key holder)
-    pub freelancer: AccountAddress, // payee
-    pub oracle: AccountAddress,     // AI verifier
-    pub plt_decimals: u8,           // display info
-    pub milestones: Vec<Milestone>, // ordered milestones
+///
+/// Milestones live in a `StateMap` keyed by id (`0..milestone_count`) so each
+/// receive call only loads the entries it touches, not the whole list.
+/// `milestone_count` doubles as the next id to assign: it only ever grows and
+/// milestones are never removed (cancel is a flag), so ids are never reused.
+///
+/// NOTE: There is no in-place migration from the earlier `Vec<Milestone>`
+/// layout. This contract has no `upgrade` entrypoint, so an instance keeps
+/// running the module it was created from; a legacy instance can never execute
+/// this code, and an in-contract `migrateState` would be unreachable. Redeploy
+/// (and replay the attestation trail from the old instance's events) instead.
+#[derive(Serial, DeserialWithState)]
+#[concordium(state_parameter = "S")]
+pub struct State<S = StateApi> {
+    pub project_id: String,              // for convenience in events/UI
+    pub client: AccountAddress,          // payer (sole key holder)
+    pub freelancer: AccountAddress,      // payee
+    pub oracles: Vec<AccountAddress>,    // AI verifiers
+    pub threshold: u32,                  // approvals needed per request (M-of-N)
+    pub plt_decimals: u8,                // display info
+    pub milestone_count: u32,            // ids are 0..milestone_count
+    pub paused: bool,                    // client kill-switch for request/confirm
+    pub require_acknowledge: bool,       // gate confirm on freelancer acknowledgement
+    pub amount_tolerance_minor: u128,    // accepted rounding slack on confirm
+    pub sequential: bool,                // enforce release order on request
+    pub confirm_window_ms: Option<u64>,  // request expires after this long
+    pub closed: bool,                    // terminal: no further mutations
+    pub currency_symbol: Option<String>, // display label for amounts
+    pub admin: Option<AccountAddress>,   // emergency pause/cancel/reset key
+    pub request_count: u64,              // oracle approvals accepted so far
+    pub confirm_count: u64,              // client payments accepted so far
+    pub logic_version: u16,              // LOGIC_VERSION of the creating module
+    pub strict_ordering: bool,           // confirm requires a prior request
+    pub require_oracle_cosign: bool,     // release needs oracleCosign too
+    pub oracle_active: bool,             // false after renounceOracle, until setOracle
+    pub event_seq: u64,                  // number of events emitted so far
+    pub milestones: StateMap<MilestoneId, Milestone, S>, // id -> milestone
+    pub tx_hash_index: StateMap<TxHash, MilestoneId, S>, // recorded PLT tx -> milestone
+    pub idempotency_keys: StateMap<IdempotencyKey, MilestoneId, S>, // applied confirm key -> milestone
+    pub oracle_bonds: StateMap<AccountAddress, TxHash, S>, // oracle seat -> latest recorded bond tx
+    pub bond_tx_index: StateMap<TxHash, AccountAddress, S>, // every recorded bond tx -> oracle
+}
+
+impl State {
+    /// Is `account` one of the configured oracles?
+    fn is_oracle(&self, account: &AccountAddress) -> bool {
+        self.oracles.contains(account)
+    }
+
+    /// Is `account` the configured admin?
+    fn is_admin(&self, account: &AccountAddress) -> bool {
+        self.admin.as_ref() == Some(account)
+    }
+
+    /// Are all dependencies of milestone `id` settled (released or cancelled)?
+    /// (Unknown ids: true; the caller's own existence check rejects them.)
+    fn dependencies_met(&self, id: MilestoneId) -> bool {
+        self.milestones.get(&id).is_none_or(|ms| {
+            ms.depends_on
+                .iter()
+                .all(|dep| self.milestones.get(dep).is_some_and(|d| d.settled()))
+        })
+    }
+
+    /// Was `tx_hash` already recorded, as a milestone transfer or an oracle bond?
+    fn tx_hash_recorded(&self, tx_hash: &TxHash) -> bool {
+        self.tx_hash_index.get(tx_hash).is_some() || self.bond_tx_index.get(tx_hash).is_some()
+    }
+
+    /// May `tx_hash` be recorded on milestone `id`? Never if it is an oracle
+    /// bond or was recorded on another milestone. Hashes stay mapped to their
+    /// milestone through `resetMilestone`, so one of `id`'s own is free again
+    /// only once `id` no longer holds it.
+    fn tx_hash_free_for(&self, tx_hash: &TxHash, id: MilestoneId) -> bool {
+        if self.bond_tx_index.get(tx_hash).is_some() {
+            return false;
+        }
+        match self.tx_hash_index.get(tx_hash) {
+            None => true,
+            Some(owner) => {
+                *owner == id
+                    && self
+                        .milestones
+                        .get(&id)
+                        .is_some_and(|ms| !ms.holds_tx_hash(tx_hash))
+            }
+        }
+    }
+
+    /// Oracle bonds in seat order (oracles without a recorded bond are skipped).
+    fn bonds(&self) -> Vec<(AccountAddress, TxHash)> {
+        self.oracles
+            .iter()
+            .filter_map(|o| self.oracle_bonds.get(o).map(|h| (*o, *h)))
+            .collect()
+    }
+
+    /// Sequence number for the next event; increments the counter so every
+    /// event of this instance gets a distinct, gap-free `seq`.
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.event_seq;
+        self.event_seq += 1;
+        seq
+    }
 }
 
 // ---- Events (logged with enable_logger) --------------------------------------
@@ -87,6 +371,7 @@ pub struct ReleaseRequestedEvent {
     pub milestone_id: MilestoneId,
     pub work_hash: Hash32,
     pub requested_at_ms: Timestamp,
+    pub seq: u64, // position in this instance's event stream
 }
 
 /// Emitted when CLIENT confirms payment (final attestation).
@@ -95,248 +380,4223 @@ pub struct AttestedEvent {
     pub project_id: String,
     pub milestone_id: MilestoneId,
     pub work_hash: Hash32,
-    pub plt_tx_hash: TxHash,
+    pub plt_tx_hash: TxHash, // final payment; earlier ones are in PartialPaymentEvent
     pub amount_minor: u128,
     pub block_time_ms: Timestamp,
+    pub freelancer: AccountAddress, // payee, so events are self-describing
+    pub token_id: String,           // PLT the payment was made in
+    pub memo: Option<String>,       // client note, if any
+    pub work_hash_count: u32,       // artifacts approved; `work_hash` is the first
+    pub elapsed_ms: u64,            // time from release request to this attestation
+    pub payer: AccountAddress,      // PLT sender named by the client
+    pub payee: AccountAddress,      // PLT receiver named by the client
+    pub logic_version: u16,         // validation rules that accepted this
+    pub seq: u64,
 }
 
-// ---- Errors ------------------------------------------------------------------
-
-/// Errors for receive entrypoints (must implement `Reject`).
-#[derive(Serial, Deserial, SchemaType, Debug, PartialEq, Eq, Reject)]
-pub enum ContractError {
-    Unauthorized,     // caller not allowed for this action
-    InvalidMilestone, // out-of-bounds index
-    AlreadyRequested, // request twice
-    NotRequested,     // confirm without prior request
-    AlreadyReleased,  // double-release attempt
-    AmountMismatch,   // client-reported paid amount != configured
-    LogError,         // failed to serialize/write event to chain log
-    ParseError,       // failed to parse parameters
+/// Emitted when CLIENT's confirms cover the amount but the oracle co-sign is
+/// still missing (`require_oracle_cosign`); `AttestedEvent` follows later.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ClientConfirmedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub plt_tx_hash: TxHash, // final payment
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
 }
 
-impl From<ParseError> for ContractError {
-    fn from(_: ParseError) -> Self {
-        ContractError::ParseError
-    }
+/// Emitted when ORACLE co-signs a milestone (`require_oracle_cosign`).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleCosignedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub oracle: AccountAddress,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
 }
 
-// ---- Init entrypoint ---------------------------------------------------------
-
-/// Initialize state with participants and milestone amounts.
-/// NOTE: No tokens move in this contract; PLT payments happen off-chain by accounts.
-#[init(contract = "paylog", parameter = "InitParams")]
-fn init(ctx: &InitContext, _sb: &mut StateBuilder) -> InitResult<State> {
-    // Parse parameters (validated by schema).
-    let p: InitParams = ctx.parameter_cursor().get()?;
-
-    // Defensive: require at least one milestone.
-    ensure!(!p.amounts.is_empty(), Reject::from(ParseError::default()));
-
-    // Build milestones array from amounts.
-    let ms = p
-        .amounts
-        .into_iter()
-        .map(|amt| Milestone {
-            amount_minor: amt,
-            requested: false,
-            released: false,
-            work_hash: None,
-            plt_tx_hash: None,
-            requested_at_ms: None,
-            attested_at_ms: None,
-        })
-        .collect::<Vec<_>>();
-
-    Ok(State {
-        project_id: p.project_id,
-        client: p.client,
-        freelancer: p.freelancer,
-        oracle: p.oracle,
-        plt_decimals: p.plt_decimals,
-        milestones: ms,
-    })
+/// Categorical reason for a cancellation or dispute, for indexer aggregation.
+#[derive(Serial, Deserial, SchemaType, Clone, Copy, PartialEq, Eq)]
+pub enum ReasonCode {
+    NoShow,          // work was never delivered
+    QualityFailure,  // delivered work did not meet the bar
+    ScopeChange,     // requirements changed
+    MutualAgreement, // both parties agreed
+    Other,           // see `reason_hash`
 }
 
-// ---- requestRelease (ORACLE -> verifies work) --------------------------------
-
-/// Params for `requestRelease`.
+/// Emitted when CLIENT, ORACLE or ADMIN cancels an unpaid milestone.
 #[derive(Serial, Deserial, SchemaType, Clone)]
-pub struct RequestParam {
-    pub milestone_id: MilestoneId, // which milestone is ready
-    pub work_hash: Hash32,         // digest of normalized diff/artifact
+pub struct MilestoneCancelledEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub block_time_ms: Timestamp,
+    pub actor: AccountAddress, // client, oracle or admin
+    pub reason: ReasonCode,
+    pub reason_hash: Hash32, // digest of the off-chain explanation
+    pub seq: u64,
 }
 
-/// Oracle-only: mark a milestone as ready-to-pay; store work hash & timestamp.
-#[receive(
-    contract = "paylog",
-    name = "requestRelease",
-    parameter = "RequestParam",
-    error = "ContractError",
-    mutable,
-    enable_logger
-)]
-fn request_release(
-    ctx: &ReceiveContext,   // call context (sender & metadata)
-    host: &mut Host<State>, // mutable state handle
-    logger: &mut Logger,    // event logger
-) -> Result<(), ContractError> {
-    // Only an account can call; contracts not allowed as oracle.
-    let sender = match ctx.sender() {
-        Address::Account(a) => a,
-        _ => return Err(ContractError::Unauthorized),
-    };
-    // Enforce oracle-only access.
-    ensure!(sender == host.state().oracle, ContractError::Unauthorized);
-
-    // Parse params.
-    let p: RequestParam = ctx.parameter_cursor().get()?;
+/// Emitted when ORACLE hands its role over to a new account.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleChangedEvent {
+    pub project_id: String,
+    pub old_oracle: AccountAddress,
+    pub new_oracle: AccountAddress,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Pull milestone (validate index).
-    let ms = host
-        .state_mut()
-        .milestones
-        .get_mut(p.milestone_id as usize)
-        .ok_or(ContractError::InvalidMilestone)?;
+/// Emitted when CLIENT or FREELANCER disputes a requested milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct DisputeOpenedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub opened_by: AccountAddress,
+    pub reason: ReasonCode,
+    pub reason_hash: Hash32,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Cannot request twice; also block post-release requests.
-    ensure!(!ms.released, ContractError::AlreadyReleased);
-    ensure!(!ms.requested, ContractError::AlreadyRequested);
+/// Emitted when ORACLE resolves a dispute (`uphold == false` cancels).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct DisputeResolvedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub uphold: bool,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Update state.
-    ms.requested = true;
-    ms.work_hash = Some(p.work_hash);
-    ms.requested_at_ms = Some(ctx.metadata().block_time());
+/// Emitted when ORACLE revokes an erroneous release request.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct RequestRevokedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub revoked_work_hash: Hash32,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Emit ReleaseRequestedEvent for UI/indexers.
-    let ev = ReleaseRequestedEvent {
-        project_id: host.state().project_id.clone(),
-        milestone_id: p.milestone_id,
-        work_hash: p.work_hash,
-        requested_at_ms: ctx.metadata().block_time(),
-    };
-    logger.log(&ev).map_err(|_| ContractError::LogError)?;
+/// Emitted when CLIENT pauses or unpauses the contract.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct PausedEvent {
+    pub project_id: String,
+    pub paused: bool,
+    pub block_time_ms: Timestamp,
+    pub actor: AccountAddress, // client or admin
+    pub seq: u64,
+}
 
-    Ok(())
+/// Emitted when CLIENT or ADMIN locks or unlocks a single milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct MilestoneLockedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub locked: bool,
+    pub block_time_ms: Timestamp,
+    pub actor: AccountAddress, // client or admin
+    pub seq: u64,
 }
 
-// ---- confirmPayment (CLIENT -> after PLT transfer) ---------------------------
+/// Emitted when CLIENT amends the amount of a not-yet-requested milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AmountAmendedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub old_amount_minor: u128,
+    pub new_amount_minor: u128,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-/// Params for `confirmPayment`.
+/// Emitted when CLIENT appends a milestone after init.
 #[derive(Serial, Deserial, SchemaType, Clone)]
-pub struct ConfirmParam {
-    pub milestone_id: MilestoneId, // must be previously requested
-    pub paid_amount_minor: u128,   // sanity check
-    pub plt_tx_hash: TxHash,       // 32-byte PLT transfer hash
+pub struct MilestoneAddedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub amount_minor: u128,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
 }
 
-/// Client-only: confirm the PLT payment and finalize attestation.
-#[receive(
-    contract = "paylog",
-    name = "confirmPayment",
-    parameter = "ConfirmParam",
-    error = "ContractError",
-    mutable,
-    enable_logger
-)]
-fn confirm_payment(
-    ctx: &ReceiveContext,
-    host: &mut Host<State>,
-    logger: &mut Logger,
-) -> Result<(), ContractError> {
-    // Only the client account can confirm.
-    let sender = match ctx.sender() {
-        Address::Account(a) => a,
-        _ => return Err(ContractError::Unauthorized),
-    };
-    ensure!(sender == host.state().client, ContractError::Unauthorized);
+/// Emitted when ORACLE pre-commits to a work hash (commit-reveal).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct WorkCommittedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub commitment: Hash32,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Parse params.
-    let p: ConfirmParam = ctx.parameter_cursor().get()?;
+/// Emitted when CLIENT confirms a payment that does not yet cover the milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct PartialPaymentEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub plt_tx_hash: TxHash,
+    pub paid_amount_minor: u128, // this payment
+    pub paid_so_far_minor: u128, // running total including this payment
+    pub amount_minor: u128,      // agreed milestone amount
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Get project_id before borrowing state_mut
-    let project_id = host.state().project_id.clone();
+/// Emitted for every oracle approval of a release request.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleApprovalEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub oracle: AccountAddress,
+    pub work_hash: Hash32,
+    pub approvals: u32, // approvals so far, including this one
+    pub threshold: u32,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Fetch milestone.
-    let ms = host
-        .state_mut()
-        .milestones
-        .get_mut(p.milestone_id as usize)
-        .ok_or(ContractError::InvalidMilestone)?;
+/// Emitted when FREELANCER acknowledges a requested milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AcknowledgedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Must have been requested by the oracle, and not yet released.
-    ensure!(ms.requested, ContractError::NotRequested);
-    ensure!(!ms.released, ContractError::AlreadyReleased);
+/// Emitted when CLIENT hands the payer role over to a new account.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ClientChangedEvent {
+    pub project_id: String,
+    pub old_client: AccountAddress,
+    pub new_client: AccountAddress,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Optional: check the amount matches the configured budget.
-    ensure!(
-        p.paid_amount_minor == ms.amount_minor,
-        ContractError::AmountMismatch
-    );
+/// Emitted when CLIENT records a PLT refund for a released milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct RefundedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub refund_tx_hash: TxHash,
+    pub amount_minor: u128,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Work hash must exist because requestRelease stored it.
-    let work_hash = ms.work_hash.expect("work_hash set at request");
-    let amount_minor = ms.amount_minor;
+/// Emitted when CLIENT records a deposit earmarking funds for a milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct DepositRecordedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub deposit_tx_hash: TxHash,
+    pub amount_minor: u128,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Finalize.
-    ms.released = true;
-    ms.plt_tx_hash = Some(p.plt_tx_hash);
-    ms.attested_at_ms = Some(ctx.metadata().block_time());
+/// Emitted when CLIENT sets or clears a milestone's external reference id.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct RefIdSetEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub ref_id: Option<String>,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    // Emit AttestedEvent.
-    let ev = AttestedEvent {
-        project_id,
-        milestone_id: p.milestone_id,
-        work_hash,
-        plt_tx_hash: p.plt_tx_hash,
-        amount_minor,
-        block_time_ms: ctx.metadata().block_time(),
-    };
-    logger.log(&ev).map_err(|_| ContractError::LogError)?;
+/// Emitted when CLIENT closes a project whose milestones are all settled.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ProjectClosedEvent {
+    pub project_id: String,
+    pub released_count: u32,
+    pub cancelled_count: u32,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-    Ok(())
+/// Emitted right after the `AttestedEvent` that releases the last unreleased
+/// milestone: a single "project complete" signal for indexers.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ProjectSettledEvent {
+    pub project_id: String,
+    pub total_amount_minor: u128, // sum of all milestone amounts
+    pub milestone_count: u32,
+    pub last_tx_hash: TxHash, // PLT tx that completed the final milestone
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
 }
 
-// ---- Read-only view ----------------------------------------------------------
+/// Emitted when ORACLE records the tx of a bond it posted off-chain.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleBondRecordedEvent {
+    pub project_id: String,
+    pub oracle: AccountAddress,
+    pub bond_tx_hash: TxHash,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
 
-/// Input for `viewMilestone`.
+/// Emitted when ADMIN (or CLIENT without an admin) wipes a milestone.
 #[derive(Serial, Deserial, SchemaType, Clone)]
-pub struct ViewParam {
+pub struct MilestoneResetEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub was_released: bool, // true only for a forced reset
+    pub block_time_ms: Timestamp,
+    pub actor: AccountAddress,
+    pub seq: u64,
+}
+
+/// Emitted when CLIENT disables all oracle seats (e.g. after a key compromise).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleRenouncedEvent {
+    pub project_id: String,
+    pub oracles: Vec<AccountAddress>, // seats disabled until setOracle
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
+
+/// Emitted when CLIENT abandons the whole project (one event, not one per milestone).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ProjectCancelledEvent {
+    pub project_id: String,
+    pub cancelled_count: u32, // milestones newly cancelled by this call
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
+
+/// Emitted when ORACLE replaces the work hash of a requested milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct WorkHashUpdatedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub old_hash: Hash32,
+    pub new_hash: Hash32,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
+
+/// Emitted when CLIENT moves a milestone deadline later.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct DeadlineExtendedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub old_deadline_ms: Timestamp,
+    pub new_deadline_ms: Timestamp,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
+
+/// Emitted when CLIENT sets or clears a milestone's category label.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct LabelSetEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub label: Option<String>,
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
+
+/// Emitted when FREELANCER stores a signed receipt for a released milestone.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ReceiptSubmittedEvent {
+    pub project_id: String,
+    pub milestone_id: MilestoneId,
+    pub plt_tx_hash: TxHash, // the signed message (final payment)
+    pub receipt_sig: [u8; 64],
+    pub block_time_ms: Timestamp,
+    pub seq: u64,
+}
+
+/// Emitted once by `init`; the creation record indexers can key instances on.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct InitEvent {
+    pub project_id: String,
+    pub client: AccountAddress,
+    pub freelancer: AccountAddress,
+    pub oracles: Vec<AccountAddress>,
+    pub threshold: u32,
+    pub plt_decimals: u8,
+    pub milestone_count: u32,
+    pub total_amount_minor: u128, // sum of initial milestone amounts
+    pub block_time_ms: Timestamp,
+    pub seq: u64, // always 0: the first event of the instance
+}
+
+// ---- Errors ------------------------------------------------------------------
+
+/// Errors for receive entrypoints (must implement `Reject`).
+#[derive(Serial, Deserial, SchemaType, Debug, PartialEq, Eq, Reject)]
+pub enum ContractError {
+    Unauthorized,          // caller not allowed for this action
+    InvalidMilestone,      // out-of-bounds index
+    AlreadyRequested,      // request twice
+    NotRequested,          // confirm without prior request
+    AlreadyReleased,       // double-release attempt
+    AmountMismatch,        // client-reported paid amount != configured
+    LogFull,               // event log is full (too many/too large events)
+    ParseError,            // failed to parse parameters
+    Overflow,              // u128 aggregate exceeded its range
+    Cancelled,             // milestone was cancelled
+    InvalidRole,           // one account would hold two roles
+    DeadlinePassed,        // request after the milestone deadline
+    Disputed,              // milestone has an open dispute
+    NotDisputed,           // resolve without an open dispute
+    InvalidTokenId,        // empty or over-long PLT token id
+    InvalidDecimals,       // plt_decimals above MAX_PLT_DECIMALS
+    MemoTooLong,           // memo above MAX_MEMO_LEN, or ids + memo above MAX_ATTESTED_TEXT_LEN
+    Paused,                // contract is paused by the client
+    CommitmentMismatch,    // revealed work_hash/salt do not match commitment
+    HashDisagreement,      // oracle approved a different work_hash
+    AlreadyApproved,       // same oracle approved twice
+    DuplicateTxHash,       // PLT tx hash already recorded on a milestone
+    NotAcknowledged,       // confirm before required freelancer acknowledgement
+    NotReleased,           // action requires a released milestone
+    PreviousNotReleased,   // sequential mode: milestone id-1 neither released nor cancelled
+    AlreadyRefunded,       // refund recorded twice
+    InvalidWorkHash,       // all-zero work hash (oracle hashing bug)
+    InvalidTxHash,         // all-zero PLT tx hash
+    ConfirmWindowExpired,  // request is older than the confirmation window
+    AlreadyDeposited,      // deposit recorded twice
+    EmptyProjectId,        // blank or whitespace-only project_id at init
+    ProjectIdTooLong,      // project_id above MAX_PROJECT_ID_LEN bytes
+    InvalidSignature,      // relayed oracle signature or key does not verify
+    MilestonesOutstanding, // close while a milestone is neither released nor cancelled
+    Closed,                // project was closed by the client
+    IdempotencyConflict,   // idempotency key already used for another milestone
+    TooManyMilestones,     // milestone count above MAX_MILESTONES
+    DeadlineNotLater,      // extension not strictly after the current deadline
+    LogMalformed,          // event exceeds the size limit or failed to serialize
+    AlreadyReceipted,      // receipt submitted twice
+    DependencyNotMet,      // a milestone in depends_on is neither released nor cancelled
+    MilestoneLocked,       // milestone is locked by the client or admin
+    AwaitingCosign,        // client already confirmed; waiting for oracleCosign
+    CosignNotRequired,     // oracleCosign on a project without require_oracle_cosign
+    InvalidProjectId,      // project_id contains control characters
+    TooManyOracles,        // oracle count above MAX_ORACLES
+}
+
+impl From<ParseError> for ContractError {
+    fn from(_: ParseError) -> Self {
+        ContractError::ParseError
+    }
+}
+
+/// Keep the host's reason for a failed `logger.log`. Returning it reverts the
+/// whole call, so state mutated before the log is never persisted.
+impl From<LogError> for ContractError {
+    fn from(e: LogError) -> Self {
+        match e {
+            LogError::Full => ContractError::LogFull,
+            LogError::Malformed => ContractError::LogMalformed,
+        }
+    }
+}
+
+/// Checked addition of minor-unit amounts; every amount sum in this contract
+/// goes through here so overflow always surfaces as `ContractError::Overflow`.
+fn add_amount(a: u128, b: u128) -> Result<u128, ContractError> {
+    a.checked_add(b).ok_or(ContractError::Overflow)
+}
+
+/// `floor(part * 100 / whole)` for `part <= whole`, exact for any `u128`
+/// (0 when `whole` is 0). The fraction is computed by two steps of decimal long
+/// division in which `10 * r` is never materialized, so nothing can overflow.
+fn percent_floor(part: u128, whole: u128) -> u8 {
+    if whole == 0 {
+        return 0;
+    }
+    let whole_part = (part / whole).min(1) as u8 * 100;
+    let mut fraction = 0;
+    let mut r = part % whole;
+    for _ in 0..2 {
+        // digit = floor(10 * r / whole), r = (10 * r) mod whole, with r < whole.
+        let mut digit = 0;
+        let mut acc: u128 = 0;
+        for _ in 0..10 {
+            if acc >= whole - r {
+                acc -= whole - r;
+                digit += 1;
+            } else {
+                acc += r;
+            }
+        }
+        fraction = fraction * 10 + digit;
+        r = acc;
+    }
+    whole_part + fraction
+}
+
+// ---- Access control ----------------------------------------------------------
+
+/// The calling account; contracts are never allowed to act in any role.
+fn require_account_sender(ctx: &ReceiveContext) -> Result<AccountAddress, ContractError> {
+    match ctx.sender() {
+        Address::Account(a) => Ok(a),
+        Address::Contract(_) => Err(ContractError::Unauthorized),
+    }
+}
+
+/// `sender` must be the single account holding the `expected` role.
+fn require_role(sender: AccountAddress, expected: AccountAddress) -> Result<(), ContractError> {
+    ensure!(sender == expected, ContractError::Unauthorized);
+    Ok(())
+}
+
+/// `account` must hold an oracle seat, and oracles must not be renounced.
+fn require_oracle(st: &State, account: AccountAddress) -> Result<(), ContractError> {
+    ensure!(
+        st.oracle_active && st.is_oracle(&account),
+        ContractError::Unauthorized
+    );
+    Ok(())
+}
+
+// ---- Init entrypoint ---------------------------------------------------------
+
+/// Version of the validation rules in this module; bump whenever they change so
+/// indexers can tell which logic produced an attestation.
+const LOGIC_VERSION: u16 = 1;
+
+/// Practical max for PLT minor-unit math (10^38 still fits in a u128).
+const MAX_PLT_DECIMALS: u8 = 38;
+
+/// Allowed milestone count per project. The upper bound keeps the full-scan
+/// views (`viewAllMilestones`, `viewProject`, ...) within energy limits.
+const MIN_MILESTONES: usize = 1;
+const MAX_MILESTONES: usize = 1000;
+
+/// Upper bound on `InitParams.project_id` length in bytes (copied into every event).
+const MAX_PROJECT_ID_LEN: usize = 128;
+
+/// Allowed oracle count. `InitEvent` and `OracleRenouncedEvent` list every
+/// oracle (32 bytes each); with a max-length project id, `InitEvent` takes
+/// 241 + 32 * n bytes, so 8 is the most that fits the host's 512-byte limit.
+const MAX_ORACLES: usize = 8;
+
+/// Upper bound on `InitParams.currency_symbol` length in bytes.
+const MAX_CURRENCY_SYMBOL_LEN: usize = 16;
+
+/// Upper bound on a milestone `ref_id` length in bytes (fits a UUID with room to spare).
+const MAX_REF_ID_LEN: usize = 64;
+
+/// Upper bound on a milestone `label` length in bytes.
+const MAX_LABEL_LEN: usize = 32;
+
+/// Is `s` free of control characters (C0/C1, incl. newlines and tabs)? Other
+/// Unicode is fine; this only keeps ids safe to display verbatim.
+fn is_printable(s: &str) -> bool {
+    !s.chars().any(char::is_control)
+}
+
+/// Does the dependency graph (milestone id -> ids it depends on) contain no
+/// cycle? Kahn's algorithm; ids must already be checked to be in range.
+fn is_acyclic(deps: &[Vec<MilestoneId>]) -> bool {
+    let mut pending: Vec<usize> = deps.iter().map(|d| d.len()).collect();
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); deps.len()];
+    for (id, d) in deps.iter().enumerate() {
+        for dep in d {
+            dependents[*dep as usize].push(id);
+        }
+    }
+    let mut ready: Vec<usize> = (0..deps.len()).filter(|id| pending[*id] == 0).collect();
+    let mut visited = 0;
+    while let Some(id) = ready.pop() {
+        visited += 1;
+        for next in dependents[id].iter() {
+            pending[*next] -= 1;
+            if pending[*next] == 0 {
+                ready.push(*next);
+            }
+        }
+    }
+    visited == deps.len()
+}
+
+/// Initialize state with participants and milestone amounts.
+/// NOTE: No tokens move in this contract; PLT payments happen off-chain by accounts.
+#[init(
+    contract = "paylog",
+    parameter = "InitParams",
+    error = "ContractError",
+    enable_logger
+)]
+fn init(
+    ctx: &InitContext,
+    state_builder: &mut StateBuilder,
+    logger: &mut Logger,
+) -> Result<State, ContractError> {
+    // Parse parameters (validated by schema).
+    let p: InitParams = ctx.parameter_cursor().get()?;
+
+    // Defensive: require at least one milestone, and not absurdly many.
+    ensure!(p.amounts.len() >= MIN_MILESTONES, ContractError::ParseError);
+    ensure!(
+        p.amounts.len() <= MAX_MILESTONES,
+        ContractError::TooManyMilestones
+    );
+
+    // Indexers key on project_id, so it must be non-blank and bounded.
+    ensure!(
+        !p.project_id.trim().is_empty(),
+        ContractError::EmptyProjectId
+    );
+    ensure!(
+        p.project_id.len() <= MAX_PROJECT_ID_LEN,
+        ContractError::ProjectIdTooLong
+    );
+    ensure!(is_printable(&p.project_id), ContractError::InvalidProjectId);
+
+    // Each role must be held by a distinct account.
+    ensure!(
+        p.client != p.freelancer
+            && !p.oracles.contains(&p.client)
+            && !p.oracles.contains(&p.freelancer),
+        ContractError::InvalidRole
+    );
+    for (i, o) in p.oracles.iter().enumerate() {
+        ensure!(!p.oracles[..i].contains(o), ContractError::InvalidRole);
+    }
+    if let Some(admin) = &p.admin {
+        ensure!(
+            *admin != p.client && *admin != p.freelancer && !p.oracles.contains(admin),
+            ContractError::InvalidRole
+        );
+    }
+
+    // Every oracle is listed in InitEvent, so the count is bounded.
+    ensure!(
+        p.oracles.len() <= MAX_ORACLES,
+        ContractError::TooManyOracles
+    );
+
+    // Quorum must be reachable: 1 <= threshold <= number of oracles.
+    ensure!(
+        p.threshold >= 1 && p.threshold as usize <= p.oracles.len(),
+        ContractError::ParseError
+    );
+
+    // Guard display scaling/labels and meaningless zero-amount milestones.
+    ensure!(
+        p.plt_decimals <= MAX_PLT_DECIMALS,
+        ContractError::InvalidDecimals
+    );
+    let currency_symbol = p.currency_symbol.filter(|s| !s.is_empty());
+    if let Some(s) = &currency_symbol {
+        ensure!(
+            s.len() <= MAX_CURRENCY_SYMBOL_LEN,
+            ContractError::ParseError
+        );
+    }
+    ensure!(
+        p.amounts.iter().all(|amt| *amt > 0),
+        ContractError::InvalidMilestone
+    );
+
+    // Deadlines, if given, must line up one-to-one with amounts.
+    let deadlines = match p.deadlines_ms {
+        Some(d) => {
+            ensure!(d.len() == p.amounts.len(), ContractError::ParseError);
+            d.into_iter().map(Some).collect::<Vec<_>>()
+        }
+        None => vec![None; p.amounts.len()],
+    };
+
+    // Reference ids, if given, also line up with amounts and are bounded.
+    let ref_ids = match p.ref_ids {
+        Some(r) => {
+            ensure!(r.len() == p.amounts.len(), ContractError::ParseError);
+            ensure!(
+                r.iter().all(|id| id.len() <= MAX_REF_ID_LEN),
+                ContractError::ParseError
+            );
+            r.into_iter()
+                .map(|id| Some(id).filter(|id| !id.is_empty()))
+                .collect::<Vec<_>>()
+        }
+        None => vec![None; p.amounts.len()],
+    };
+
+    // Labels, if given, also line up with amounts and are bounded.
+    let labels = match p.labels {
+        Some(l) => {
+            ensure!(l.len() == p.amounts.len(), ContractError::ParseError);
+            ensure!(
+                l.iter().all(|label| label.len() <= MAX_LABEL_LEN),
+                ContractError::ParseError
+            );
+            l.into_iter()
+                .map(|label| Some(label).filter(|label| !label.is_empty()))
+                .collect::<Vec<_>>()
+        }
+        None => vec![None; p.amounts.len()],
+    };
+
+    // Per-milestone oracles, if given, line up with amounts and are oracles.
+    let assigned_oracles = match p.oracle_per_milestone {
+        Some(o) => {
+            ensure!(o.len() == p.amounts.len(), ContractError::ParseError);
+            ensure!(
+                o.iter().all(|a| p.oracles.contains(a)),
+                ContractError::InvalidRole
+            );
+            o.into_iter().map(Some).collect::<Vec<_>>()
+        }
+        None => vec![None; p.amounts.len()],
+    };
+
+    // Dependencies, if given, line up with amounts, point at existing ids and
+    // form no cycle (a self-dependency counts as one).
+    let dependencies = match p.depends_on {
+        Some(d) => {
+            ensure!(d.len() == p.amounts.len(), ContractError::ParseError);
+            ensure!(
+                d.iter()
+                    .flatten()
+                    .all(|dep| (*dep as usize) < p.amounts.len()),
+                ContractError::ParseError
+            );
+            ensure!(is_acyclic(&d), ContractError::ParseError);
+            d
+        }
+        None => vec![Vec::new(); p.amounts.len()],
+    };
+
+    // Per-milestone decimals, if given, line up with amounts and are bounded.
+    let decimals = match p.decimals_per_milestone {
+        Some(d) => {
+            ensure!(d.len() == p.amounts.len(), ContractError::ParseError);
+            ensure!(
+                d.iter().all(|x| *x <= MAX_PLT_DECIMALS),
+                ContractError::InvalidDecimals
+            );
+            d
+        }
+        None => vec![p.plt_decimals; p.amounts.len()],
+    };
+
+    // Build milestones map from amounts (id == position in `amounts`).
+    let milestone_count = p.amounts.len() as u32;
+    let mut total_amount_minor: u128 = 0;
+    let mut ms = state_builder.new_map();
+    let params = p
+        .amounts
+        .into_iter()
+        .zip(deadlines)
+        .zip(ref_ids)
+        .zip(labels)
+        .zip(assigned_oracles)
+        .zip(dependencies)
+        .zip(decimals);
+    for (
+        id,
+        ((((((amt, deadline_ms), ref_id), label), assigned_oracle), depends_on), plt_decimals),
+    ) in params.enumerate()
+    {
+        total_amount_minor = add_amount(total_amount_minor, amt)?;
+        let mut milestone = Milestone::new(amt, deadline_ms, plt_decimals);
+        milestone.ref_id = ref_id;
+        milestone.label = label;
+        milestone.assigned_oracle = assigned_oracle;
+        milestone.depends_on = depends_on;
+        let _ = ms.insert(id as MilestoneId, milestone);
+    }
+
+    let mut state = State {
+        project_id: p.project_id,
+        client: p.client,
+        freelancer: p.freelancer,
+        oracles: p.oracles,
+        threshold: p.threshold,
+        plt_decimals: p.plt_decimals,
+        milestone_count,
+        paused: false,
+        require_acknowledge: p.require_acknowledge,
+        amount_tolerance_minor: p.amount_tolerance_minor.unwrap_or(0),
+        sequential: p.sequential,
+        confirm_window_ms: p.confirm_window_ms,
+        closed: false,
+        currency_symbol,
+        admin: p.admin,
+        request_count: 0,
+        confirm_count: 0,
+        logic_version: LOGIC_VERSION,
+        strict_ordering: p.strict_ordering,
+        require_oracle_cosign: p.require_oracle_cosign,
+        oracle_active: true,
+        event_seq: 0,
+        milestones: ms,
+        tx_hash_index: state_builder.new_map(),
+        oracle_bonds: state_builder.new_map(),
+        bond_tx_index: state_builder.new_map(),
+        idempotency_keys: state_builder.new_map(),
+    };
+
+    // Emit InitEvent.
+    let ev = InitEvent {
+        project_id: state.project_id.clone(),
+        client: state.client,
+        freelancer: state.freelancer,
+        oracles: state.oracles.clone(),
+        threshold: state.threshold,
+        plt_decimals: state.plt_decimals,
+        milestone_count,
+        total_amount_minor,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: state.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(state)
+}
+
+// ---- requestRelease (ORACLE -> verifies work) --------------------------------
+
+/// Params for `requestRelease`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct RequestParam {
+    pub milestone_id: MilestoneId,        // which milestone is ready
+    pub work_hash: Hash32,                // digest of normalized diff/artifact
+    pub salt: Option<[u8; 32]>,           // reveal for a prior `commitWork`, if any
+    pub work_hashes: Option<Vec<Hash32>>, // extra artifacts (logs, screenshots) after `work_hash`
+    pub signed: Option<OracleSignature>,  // relayed approval; None -> sender must be an oracle
+}
+
+/// Detached oracle approval so any account can relay the oracle's verification.
+/// The signed message is
+/// `to_bytes(&(contract address, milestone_id, request_nonce, work_hash, extras))`
+/// where `extras` is `work_hashes.unwrap_or_default()`. This binds the signature
+/// to this instance, milestone and full artifact set; `request_nonce` (see
+/// `MilestoneView`) changes on every reopen/reset, so old signatures cannot be
+/// replayed.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleSignature {
+    pub oracle: AccountAddress,          // oracle the approval counts for
+    pub oracle_pubkey: PublicKeyEd25519, // must be one of that account's keys
+    pub signature: SignatureEd25519,
+}
+
+/// Max artifact hashes per milestone, counting `work_hash` itself.
+const MAX_WORK_HASHES: usize = 16;
+
+/// Oracle-only: approve a milestone as ready-to-pay. Once `threshold` distinct
+/// oracles approved the same work hash, the milestone is marked requested and
+/// the work hash & timestamp are stored.
+/// If an oracle committed via `commitWork`, `salt` must open that commitment.
+/// Any account may submit the call when `signed` carries a valid oracle signature.
+#[receive(
+    contract = "paylog",
+    name = "requestRelease",
+    parameter = "RequestParam",
+    error = "ContractError",
+    mutable,
+    enable_logger,
+    crypto_primitives
+)]
+fn request_release(
+    ctx: &ReceiveContext,                 // call context (sender & metadata)
+    host: &mut Host<State>,               // mutable state handle
+    logger: &mut Logger,                  // event logger
+    crypto_primitives: &CryptoPrimitives, // sha256 commit-reveal, ed25519 relay
+) -> Result<(), ContractError> {
+    // Parse params.
+    let p: RequestParam = ctx.parameter_cursor().get()?;
+
+    // Enforce oracle-only access (directly or via a relayed signature).
+    let oracle = resolve_approver(ctx, host, crypto_primitives, &p)?;
+
+    apply_request(ctx, host, logger, crypto_primitives, oracle, &p)
+}
+
+/// Max bytes accepted by `requestReleaseWithData` (bounds on-chain hashing energy).
+const MAX_WORK_DATA_LEN: usize = 4096;
+
+/// Params for `requestReleaseWithData`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct RequestWithDataParam {
+    pub milestone_id: MilestoneId, // which milestone is ready
+    pub data: Vec<u8>,             // raw artifact; work_hash = sha256(data)
+}
+
+/// Oracle-only: like `requestRelease`, but the contract derives the work hash
+/// as `sha256(data)` itself, so hash and artifact cannot drift apart.
+/// Only meant for small artifacts (at most `MAX_WORK_DATA_LEN` bytes).
+#[receive(
+    contract = "paylog",
+    name = "requestReleaseWithData",
+    parameter = "RequestWithDataParam",
+    error = "ContractError",
+    mutable,
+    enable_logger,
+    crypto_primitives
+)]
+fn request_release_with_data(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+) -> Result<(), ContractError> {
+    // Parse params.
+    let d: RequestWithDataParam = ctx.parameter_cursor().get()?;
+    ensure!(d.data.len() <= MAX_WORK_DATA_LEN, ContractError::ParseError);
+
+    let p = RequestParam {
+        milestone_id: d.milestone_id,
+        work_hash: crypto_primitives.hash_sha2_256(&d.data).0,
+        salt: None,
+        work_hashes: None,
+        signed: None,
+    };
+
+    // Enforce oracle-only access.
+    let oracle = resolve_approver(ctx, host, crypto_primitives, &p)?;
+
+    apply_request(ctx, host, logger, crypto_primitives, oracle, &p)
+}
+
+/// The oracle an approval counts for: the signer of `p.signed` if present
+/// (after checking the key belongs to that account and the signature is valid),
+/// otherwise the sending account. Either way it must be a configured oracle.
+fn resolve_approver(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+    crypto_primitives: &CryptoPrimitives,
+    p: &RequestParam,
+) -> Result<AccountAddress, ContractError> {
+    let approver = match &p.signed {
+        Some(sig) => {
+            let keys = host
+                .account_public_keys(sig.oracle)
+                .map_err(|_| ContractError::InvalidSignature)?;
+            let key = PublicKey::Ed25519(sig.oracle_pubkey);
+            ensure!(
+                keys.keys
+                    .values()
+                    .any(|c| c.keys.values().any(|k| *k == key)),
+                ContractError::InvalidSignature
+            );
+            let request_nonce = host
+                .state()
+                .milestones
+                .get(&p.milestone_id)
+                .map(|m| m.request_nonce)
+                .ok_or(ContractError::InvalidMilestone)?;
+            let extras = p.work_hashes.clone().unwrap_or_default();
+            let message = to_bytes(&(
+                ctx.self_address(),
+                p.milestone_id,
+                request_nonce,
+                p.work_hash,
+                extras,
+            ));
+            ensure!(
+                crypto_primitives.verify_ed25519_signature(
+                    sig.oracle_pubkey,
+                    sig.signature,
+                    &message
+                ),
+                ContractError::InvalidSignature
+            );
+            sig.oracle
+        }
+        // Only an account can call; contracts not allowed as oracle.
+        None => require_account_sender(ctx)?,
+    };
+    require_oracle(host.state(), approver)?;
+    Ok(approver)
+}
+
+/// Request checks shared by `requestRelease` and implicit requests on confirm:
+/// work must be verified before the milestone deadline (if any), and the reveal
+/// must match the commitment, when one was made.
+fn check_deadline_and_reveal(
+    ms: &Milestone,
+    now: Timestamp,
+    work_hash: &Hash32,
+    salt: Option<[u8; 32]>,
+    crypto_primitives: &CryptoPrimitives,
+) -> Result<(), ContractError> {
+    if let Some(deadline) = ms.deadline_ms {
+        ensure!(now <= deadline, ContractError::DeadlinePassed);
+    }
+    if let Some(commitment) = ms.work_commitment {
+        let salt = salt.ok_or(ContractError::CommitmentMismatch)?;
+        let mut preimage = [0u8; 64];
+        preimage[..32].copy_from_slice(work_hash);
+        preimage[32..].copy_from_slice(&salt);
+        ensure!(
+            crypto_primitives.hash_sha2_256(&preimage).0 == commitment,
+            ContractError::CommitmentMismatch
+        );
+    }
+    Ok(())
+}
+
+/// Max items accepted by `requestReleaseBatch` / `confirmPaymentBatch`. At 32
+/// worst-case items (15 extra work hashes per request; 256-byte memo and
+/// idempotency key per confirm) the batches measured ~53,600 and ~146,900 NRG
+/// (`max_len_batches_fit_energy_limit` in `tests/paylog.rs`), under 5% of the
+/// 3,000,000 NRG transaction limit. The cap is conservative, not a maximum.
+const MAX_BATCH_LEN: usize = 32;
+
+/// Oracle-only: approve several milestones in one transaction (each item may
+/// instead carry a relayed oracle signature). All-or-nothing: any failing item
+/// rejects the call, which reverts every state change made by earlier items.
+#[receive(
+    contract = "paylog",
+    name = "requestReleaseBatch",
+    parameter = "Vec<RequestParam>",
+    error = "ContractError",
+    mutable,
+    enable_logger,
+    crypto_primitives
+)]
+fn request_release_batch(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+) -> Result<(), ContractError> {
+    // Parse params.
+    let items: Vec<RequestParam> = ctx.parameter_cursor().get()?;
+    ensure!(items.len() <= MAX_BATCH_LEN, ContractError::ParseError);
+
+    for p in items.iter() {
+        // Enforce oracle-only access per item.
+        let oracle = resolve_approver(ctx, host, crypto_primitives, p)?;
+        apply_request(ctx, host, logger, crypto_primitives, oracle, p)?;
+    }
+
+    Ok(())
+}
+
+/// Validate and record a single oracle approval (caller already authorized).
+fn apply_request(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+    oracle: AccountAddress,
+    p: &RequestParam,
+) -> Result<(), ContractError> {
+    // No requests while the client has paused or closed the contract.
+    ensure!(!host.state().closed, ContractError::Closed);
+    ensure!(!host.state().paused, ContractError::Paused);
+    ensure!(p.work_hash != [0u8; 32], ContractError::InvalidWorkHash);
+
+    // Primary hash first, then any extra artifacts; all bounded and non-zero.
+    let mut work_hashes = vec![p.work_hash];
+    if let Some(extra) = &p.work_hashes {
+        ensure!(extra.len() < MAX_WORK_HASHES, ContractError::ParseError);
+        ensure!(
+            extra.iter().all(|h| *h != [0u8; 32]),
+            ContractError::InvalidWorkHash
+        );
+        work_hashes.extend_from_slice(extra);
+    }
+
+    // Get project_id/threshold before borrowing state_mut
+    let project_id = host.state().project_id.clone();
+    let threshold = host.state().threshold;
+
+    // Sequential projects release milestones strictly in id order
+    // (out-of-range ids fall through to the InvalidMilestone check below).
+    let st = host.state();
+    if st.sequential && p.milestone_id > 0 && p.milestone_id < st.milestone_count {
+        let prev_released = st
+            .milestones
+            .get(&(p.milestone_id - 1))
+            .is_some_and(|prev| prev.settled());
+        ensure!(prev_released, ContractError::PreviousNotReleased);
+    }
+    ensure!(
+        st.dependencies_met(p.milestone_id),
+        ContractError::DependencyNotMet
+    );
+
+    // Pull milestone (validate id).
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Cannot request twice; also block post-release, cancelled and locked requests.
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(!ms.locked, ContractError::MilestoneLocked);
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.requested, ContractError::AlreadyRequested);
+
+    // A milestone with an assigned oracle is verified by that oracle alone.
+    let threshold = match ms.assigned_oracle {
+        Some(assigned) => {
+            ensure!(oracle == assigned, ContractError::Unauthorized);
+            1
+        }
+        None => threshold,
+    };
+
+    check_deadline_and_reveal(
+        &ms,
+        ctx.metadata().block_time(),
+        &p.work_hash,
+        p.salt,
+        crypto_primitives,
+    )?;
+
+    // All approvers must agree on the artifact set; each oracle approves once.
+    if ms.work_hash.is_some() {
+        ensure!(
+            ms.work_hashes == work_hashes,
+            ContractError::HashDisagreement
+        );
+    }
+    ensure!(
+        !ms.oracle_approvals.contains(&oracle),
+        ContractError::AlreadyApproved
+    );
+
+    // Record the approval.
+    ms.work_hash = Some(p.work_hash);
+    ms.work_hashes = work_hashes;
+    ms.oracle_approvals.push(oracle);
+    let approvals = ms.oracle_approvals.len() as u32;
+
+    // Update state once the quorum is reached.
+    let quorum_reached = approvals >= threshold;
+    if quorum_reached {
+        ms.requested = true;
+        ms.requested_at_ms = Some(ctx.metadata().block_time());
+    }
+    drop(ms);
+    host.state_mut().request_count += 1;
+
+    // Emit OracleApprovalEvent for every signature.
+    let ev = OracleApprovalEvent {
+        project_id: project_id.clone(),
+        milestone_id: p.milestone_id,
+        oracle,
+        work_hash: p.work_hash,
+        approvals,
+        threshold,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: host.state_mut().next_seq(),
+    };
+    logger.log(&ev)?;
+
+    // Quorum not reached yet.
+    if !quorum_reached {
+        return Ok(());
+    }
+
+    // Emit ReleaseRequestedEvent for UI/indexers.
+    let ev = ReleaseRequestedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        work_hash: p.work_hash,
+        requested_at_ms: ctx.metadata().block_time(),
+        seq: host.state_mut().next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- commitWork (ORACLE -> optional pre-commit) ------------------------------
+
+/// Params for `commitWork`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct CommitWorkParam {
+    pub milestone_id: MilestoneId, // must not be requested yet
+    pub commitment: Hash32,        // sha256(work_hash || salt)
+}
+
+/// Oracle-only: commit to a work hash ahead of `requestRelease`, so the hash
+/// cannot be front-run once revealed. Re-committing before the request overwrites.
+#[receive(
+    contract = "paylog",
+    name = "commitWork",
+    parameter = "CommitWorkParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn commit_work(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Enforce oracle-only access.
+    let sender = require_account_sender(ctx)?;
+    require_oracle(host.state(), sender)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: CommitWorkParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Commitments only make sense before the request.
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.requested, ContractError::AlreadyRequested);
+
+    ms.work_commitment = Some(p.commitment);
+
+    // Emit WorkCommittedEvent.
+    let ev = WorkCommittedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        commitment: p.commitment,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- confirmPayment (CLIENT -> after PLT transfer) ---------------------------
+
+/// Params for `confirmPayment`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ConfirmParam {
+    pub milestone_id: MilestoneId, // must be previously requested
+    pub paid_amount_minor: u128,   // this payment; may be a partial amount
+    pub plt_tx_hash: TxHash,       // 32-byte PLT transfer hash
+    pub token_id: String,          // PLT token id, e.g. "PAYLOGPLT"
+    pub memo: Option<String>,      // optional note; "" is treated as None
+    pub idempotency_key: Option<IdempotencyKey>, // retry-safe key; repeats are no-ops
+    pub payer: AccountAddress,     // PLT sender; must be the client
+    pub payee: AccountAddress,     // PLT receiver; must be the freelancer
+    pub work_hash: Option<Hash32>, // required to implicitly request (non-strict ordering only)
+    pub salt: Option<[u8; 32]>,    // commitment reveal for an implicit request, if any
+}
+
+/// Upper bound on `ConfirmParam.token_id` length in bytes.
+const MAX_TOKEN_ID_LEN: usize = 128;
+
+/// Upper bound on `ConfirmParam.memo` length in bytes (matches PLT memo size).
+const MAX_MEMO_LEN: usize = 256;
+
+/// Upper bound on `project_id + token_id + memo` bytes. `AttestedEvent` carries
+/// all three, its other fields take 223 bytes, and the host rejects events over
+/// 512 bytes; so the per-field caps above cannot all be used at once.
+const MAX_ATTESTED_TEXT_LEN: usize = 512 - 223;
+
+/// Client-only: confirm the PLT payment and finalize attestation.
+#[receive(
+    contract = "paylog",
+    name = "confirmPayment",
+    parameter = "ConfirmParam",
+    error = "ContractError",
+    mutable,
+    enable_logger,
+    crypto_primitives
+)]
+fn confirm_payment(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+) -> Result<(), ContractError> {
+    // Only the client account can confirm.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+
+    // Parse params.
+    let p: ConfirmParam = ctx.parameter_cursor().get()?;
+
+    apply_confirm(ctx, host, logger, crypto_primitives, &p)
+}
+
+/// Client-only: confirm several PLT payments in one transaction.
+/// All-or-nothing: any failing item rejects the call, which reverts every
+/// state change made by earlier items.
+#[receive(
+    contract = "paylog",
+    name = "confirmPaymentBatch",
+    parameter = "Vec<ConfirmParam>",
+    error = "ContractError",
+    mutable,
+    enable_logger,
+    crypto_primitives
+)]
+fn confirm_payment_batch(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+) -> Result<(), ContractError> {
+    // Only the client account can confirm.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+
+    // Parse params.
+    let items: Vec<ConfirmParam> = ctx.parameter_cursor().get()?;
+    ensure!(items.len() <= MAX_BATCH_LEN, ContractError::ParseError);
+
+    for p in items.iter() {
+        apply_confirm(ctx, host, logger, crypto_primitives, p)?;
+    }
+
+    Ok(())
+}
+
+/// Validate and finalize a single confirmation (caller already authorized).
+fn apply_confirm(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+    p: &ConfirmParam,
+) -> Result<(), ContractError> {
+    // A retried key is a no-op for its own milestone (it was applied already)
+    // and a conflict for any other one.
+    if let Some(key) = &p.idempotency_key {
+        if let Some(id) = host.state().idempotency_keys.get(key) {
+            ensure!(*id == p.milestone_id, ContractError::IdempotencyConflict);
+            return Ok(());
+        }
+    }
+
+    // No confirmations while the client has paused or closed the contract.
+    ensure!(!host.state().closed, ContractError::Closed);
+    ensure!(!host.state().paused, ContractError::Paused);
+
+    // Unknown ids fail before any parameter or lifecycle check, so
+    // `InvalidMilestone` always means "no such milestone" and `NotRequested`
+    // always means "exists, but not requested yet".
+    ensure!(
+        host.state().milestones.get(&p.milestone_id).is_some(),
+        ContractError::InvalidMilestone
+    );
+
+    // Token id must be present and bounded.
+    ensure!(
+        !p.token_id.is_empty() && p.token_id.len() <= MAX_TOKEN_ID_LEN,
+        ContractError::InvalidTokenId
+    );
+
+    // Memo is optional and bounded; an empty memo is stored as None.
+    let memo = p.memo.clone().filter(|m| !m.is_empty());
+    if let Some(m) = &memo {
+        ensure!(m.len() <= MAX_MEMO_LEN, ContractError::MemoTooLong);
+    }
+
+    // Reject up front what `AttestedEvent` could never log; the memo is the
+    // field the client is most likely to shorten.
+    let text_len =
+        host.state().project_id.len() + p.token_id.len() + memo.as_ref().map_or(0, |m| m.len());
+    ensure!(
+        text_len <= MAX_ATTESTED_TEXT_LEN,
+        ContractError::MemoTooLong
+    );
+
+    // A zero payment attests nothing, even when it would land within tolerance.
+    ensure!(p.paid_amount_minor > 0, ContractError::AmountMismatch);
+
+    // An all-zero hash is never a real transfer.
+    ensure!(p.plt_tx_hash != [0u8; 32], ContractError::InvalidTxHash);
+
+    // A single PLT transfer can only ever be counted once.
+    ensure!(
+        host.state()
+            .tx_hash_free_for(&p.plt_tx_hash, p.milestone_id),
+        ContractError::DuplicateTxHash
+    );
+
+    // Get project_id/freelancer/flags before borrowing state_mut
+    let project_id = host.state().project_id.clone();
+    let freelancer = host.state().freelancer;
+
+    // The transfer's parties must be the project's client and freelancer.
+    ensure!(p.payer == host.state().client, ContractError::Unauthorized);
+    ensure!(p.payee == freelancer, ContractError::InvalidRole);
+
+    let require_acknowledge = host.state().require_acknowledge;
+    let tolerance = host.state().amount_tolerance_minor;
+    let confirm_window_ms = host.state().confirm_window_ms;
+    let strict_ordering = host.state().strict_ordering;
+    let require_oracle_cosign = host.state().require_oracle_cosign;
+    let now = ctx.metadata().block_time();
+
+    // Sequential order also applies to implicit requests (see below).
+    let st = host.state();
+    let previous_released = !st.sequential
+        || p.milestone_id == 0
+        || st
+            .milestones
+            .get(&(p.milestone_id - 1))
+            .is_some_and(|prev| prev.settled());
+    let dependencies_met = st.dependencies_met(p.milestone_id);
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Must have been requested by the oracle, and not yet released/cancelled.
+    // Without strict ordering, the client may request implicitly; a work hash
+    // is then mandatory so every requested milestone still carries one.
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(!ms.locked, ContractError::MilestoneLocked);
+    let implicit_request = !ms.requested;
+    if implicit_request {
+        ensure!(!strict_ordering, ContractError::NotRequested);
+        ensure!(previous_released, ContractError::PreviousNotReleased);
+        ensure!(dependencies_met, ContractError::DependencyNotMet);
+        let work_hash = p.work_hash.ok_or(ContractError::NotRequested)?;
+        ensure!(work_hash != [0u8; 32], ContractError::InvalidWorkHash);
+        check_deadline_and_reveal(&ms, now, &work_hash, p.salt, crypto_primitives)?;
+        if let Some(existing) = ms.work_hash {
+            ensure!(existing == work_hash, ContractError::HashDisagreement);
+        }
+        ms.requested = true;
+        ms.requested_at_ms = Some(now);
+        ms.work_hash = Some(work_hash);
+        ms.work_hashes = vec![work_hash];
+    }
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.client_confirmed, ContractError::AwaitingCosign);
+    ensure!(!ms.disputed, ContractError::Disputed);
+    ensure!(
+        !require_acknowledge || ms.acknowledged,
+        ContractError::NotAcknowledged
+    );
+
+    // A request older than the window is void; the oracle must reopen and
+    // request again. Confirming exactly at the window edge is still allowed.
+    if let (Some(window), Some(requested_at)) = (confirm_window_ms, ms.requested_at_ms) {
+        let elapsed = now
+            .timestamp_millis()
+            .saturating_sub(requested_at.timestamp_millis());
+        ensure!(elapsed <= window, ContractError::ConfirmWindowExpired);
+    }
+
+    // Payments accumulate and may exceed the configured budget only by the
+    // rounding tolerance.
+    let paid_so_far_minor = add_amount(ms.paid_so_far_minor, p.paid_amount_minor)?;
+    ensure!(
+        paid_so_far_minor <= ms.amount_minor.saturating_add(tolerance),
+        ContractError::AmountMismatch
+    );
+
+    // Work hash must exist because requestRelease stored it.
+    let work_hash = ms.work_hash.expect("work_hash set at request");
+    let amount_minor = ms.amount_minor;
+
+    // Record this payment.
+    ms.paid_so_far_minor = paid_so_far_minor;
+    ms.plt_tx_hashes.push(p.plt_tx_hash);
+    ms.idempotency_keys.extend(p.idempotency_key);
+    ms.plt_token_id = Some(p.token_id.clone());
+    ms.memo = memo;
+    ms.payer = Some(p.payer);
+    ms.payee = Some(p.payee);
+
+    // Finalize once the accumulated payments are within tolerance of the
+    // milestone amount; the exact figure stays in `paid_so_far_minor`.
+    // Co-signed projects also wait for the oracle's `oracleCosign`.
+    let fully_paid = amount_minor.abs_diff(paid_so_far_minor) <= tolerance;
+    let awaiting_cosign = fully_paid && require_oracle_cosign && !ms.oracle_cosigned;
+    if fully_paid {
+        ms.client_confirmed = true;
+    }
+    drop(ms);
+    host.state_mut().confirm_count += 1;
+    if implicit_request {
+        host.state_mut().request_count += 1;
+    }
+
+    // Index the tx hash for duplicate detection and `findByTxHash`.
+    let _ = host
+        .state_mut()
+        .tx_hash_index
+        .insert(p.plt_tx_hash, p.milestone_id);
+    if let Some(key) = p.idempotency_key {
+        let _ = host
+            .state_mut()
+            .idempotency_keys
+            .insert(key, p.milestone_id);
+    }
+
+    // Implicit request: emit ReleaseRequestedEvent before the payment event.
+    if implicit_request {
+        let ev = ReleaseRequestedEvent {
+            project_id: project_id.clone(),
+            milestone_id: p.milestone_id,
+            work_hash,
+            requested_at_ms: now,
+            seq: host.state_mut().next_seq(),
+        };
+        logger.log(&ev)?;
+    }
+
+    // Not fully paid yet: emit PartialPaymentEvent and stop here.
+    if !fully_paid {
+        let ev = PartialPaymentEvent {
+            project_id,
+            milestone_id: p.milestone_id,
+            plt_tx_hash: p.plt_tx_hash,
+            paid_amount_minor: p.paid_amount_minor,
+            paid_so_far_minor,
+            amount_minor,
+            block_time_ms: ctx.metadata().block_time(),
+            seq: host.state_mut().next_seq(),
+        };
+        logger.log(&ev)?;
+        return Ok(());
+    }
+
+    // Fully paid, but the oracle has not co-signed: emit ClientConfirmedEvent.
+    if awaiting_cosign {
+        let ev = ClientConfirmedEvent {
+            project_id,
+            milestone_id: p.milestone_id,
+            plt_tx_hash: p.plt_tx_hash,
+            block_time_ms: now,
+            seq: host.state_mut().next_seq(),
+        };
+        logger.log(&ev)?;
+        return Ok(());
+    }
+
+    release_milestone(ctx, host, logger, p.milestone_id)
+}
+
+/// Marks a fully paid (and, if required, co-signed) milestone released and
+/// emits `AttestedEvent`, plus `ProjectSettledEvent` if it was the last one.
+/// Payment details come from the milestone's latest recorded confirm.
+fn release_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    milestone_id: MilestoneId,
+) -> Result<(), ContractError> {
+    let project_id = host.state().project_id.clone();
+    let freelancer = host.state().freelancer;
+    let now = ctx.metadata().block_time();
+
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+    ms.released = true;
+    ms.attested_at_ms = Some(now);
+    let m = ms.clone();
+    drop(ms);
+
+    // Verification-to-payment latency; saturates to 0 if clocks look inverted.
+    let elapsed_ms = m.requested_at_ms.map_or(0, |t| {
+        now.timestamp_millis().saturating_sub(t.timestamp_millis())
+    });
+    let plt_tx_hash = *m.plt_tx_hashes.last().expect("tx hash recorded at confirm");
+
+    // Emit AttestedEvent.
+    let ev = AttestedEvent {
+        project_id: project_id.clone(),
+        milestone_id,
+        work_hash: m.work_hash.expect("work_hash set at request"),
+        plt_tx_hash,
+        amount_minor: m.amount_minor,
+        block_time_ms: now,
+        freelancer,
+        token_id: m.plt_token_id.expect("token id recorded at confirm"),
+        memo: m.memo,
+        work_hash_count: m.work_hashes.len() as u32,
+        elapsed_ms,
+        payer: m.payer.expect("payer recorded at confirm"),
+        payee: m.payee.expect("payee recorded at confirm"),
+        logic_version: host.state().logic_version,
+        seq: host.state_mut().next_seq(),
+    };
+    logger.log(&ev)?;
+
+    // Last milestone released: emit ProjectSettledEvent.
+    let mut all_released = true;
+    let mut total_amount_minor: u128 = 0;
+    for (_, m) in host.state().milestones.iter() {
+        if !m.released {
+            all_released = false;
+            break;
+        }
+        total_amount_minor = add_amount(total_amount_minor, m.amount_minor)?;
+    }
+    if all_released {
+        let ev = ProjectSettledEvent {
+            project_id,
+            total_amount_minor,
+            milestone_count: host.state().milestone_count,
+            last_tx_hash: plt_tx_hash,
+            block_time_ms: now,
+            seq: host.state_mut().next_seq(),
+        };
+        logger.log(&ev)?;
+    }
+
+    Ok(())
+}
+
+// ---- oracleCosign (ORACLE -> second signature on confirm) --------------------
+
+/// Params for `oracleCosign`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct CosignParam {
+    pub milestone_id: MilestoneId, // must be requested, not yet released
+}
+
+/// Oracle-only (the assigned oracle, if any): co-sign a requested milestone on
+/// a `require_oracle_cosign` project. Releases it if the client has already
+/// confirmed the full amount; otherwise the client's final confirm releases it.
+#[receive(
+    contract = "paylog",
+    name = "oracleCosign",
+    parameter = "CosignParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn oracle_cosign(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only an oracle account can co-sign.
+    let sender = require_account_sender(ctx)?;
+    require_oracle(host.state(), sender)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+    ensure!(!host.state().paused, ContractError::Paused);
+    ensure!(
+        host.state().require_oracle_cosign,
+        ContractError::CosignNotRequired
+    );
+
+    // Parse params.
+    let p: CosignParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(!ms.locked, ContractError::MilestoneLocked);
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(ms.requested, ContractError::NotRequested);
+    ensure!(!ms.disputed, ContractError::Disputed);
+    ensure!(!ms.oracle_cosigned, ContractError::AlreadyApproved);
+    if let Some(assigned) = ms.assigned_oracle {
+        ensure!(sender == assigned, ContractError::Unauthorized);
+    }
+
+    ms.oracle_cosigned = true;
+    let client_confirmed = ms.client_confirmed;
+    drop(ms);
+
+    // Emit OracleCosignedEvent.
+    let ev = OracleCosignedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        oracle: sender,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: host.state_mut().next_seq(),
+    };
+    logger.log(&ev)?;
+
+    // Both signatures present: release now.
+    if client_confirmed {
+        release_milestone(ctx, host, logger, p.milestone_id)?;
+    }
+
+    Ok(())
+}
+
+// ---- cancelMilestone (CLIENT, ORACLE or ADMIN -> abandon unpaid milestone) ---
+
+/// Params for `cancelMilestone`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct CancelParam {
+    pub milestone_id: MilestoneId, // must not be released
+    pub reason: ReasonCode,        // why it is being cancelled
+    pub reason_hash: Hash32,       // digest of the off-chain explanation
+}
+
+/// Client-, oracle- or admin-only: mark an unpaid milestone as cancelled.
+#[receive(
+    contract = "paylog",
+    name = "cancelMilestone",
+    parameter = "CancelParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn cancel_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // The client, an oracle or the admin may cancel.
+    let sender = require_account_sender(ctx)?;
+    let st = host.state();
+    ensure!(
+        sender == st.client || require_oracle(st, sender).is_ok() || st.is_admin(&sender),
+        ContractError::Unauthorized
+    );
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: CancelParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Paid milestones are final; cancelling twice is an error.
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+
+    ms.cancelled = true;
+
+    // Emit MilestoneCancelledEvent.
+    let ev = MilestoneCancelledEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        block_time_ms: ctx.metadata().block_time(),
+        actor: sender,
+        reason: p.reason,
+        reason_hash: p.reason_hash,
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- setOracle (ORACLE -> key rotation) --------------------------------------
+
+/// Params for `setOracle`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct SetOracleParam {
+    pub new_oracle: AccountAddress,         // must not already hold a role
+    pub old_oracle: Option<AccountAddress>, // seat to replace; client calls only
+}
+
+/// Oracle-only: rotate the caller's oracle seat to a new account.
+/// After `renounceOracle`, only the client may call, naming `old_oracle`; the
+/// rotation re-enables the oracle seats.
+#[receive(
+    contract = "paylog",
+    name = "setOracle",
+    parameter = "SetOracleParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn set_oracle(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the current oracle account can rotate; once renounced, the client.
+    let sender = require_account_sender(ctx)?;
+    let oracle_active = host.state().oracle_active;
+    if oracle_active {
+        require_oracle(host.state(), sender)?;
+    } else {
+        require_role(sender, host.state().client)?;
+    }
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: SetOracleParam = ctx.parameter_cursor().get()?;
+    let old_oracle = if oracle_active {
+        sender
+    } else {
+        let old = p.old_oracle.ok_or(ContractError::ParseError)?;
+        ensure!(host.state().is_oracle(&old), ContractError::InvalidRole);
+        old
+    };
+
+    // The oracle must stay independent of the paying parties, the admin and
+    // other oracles.
+    let st = host.state_mut();
+    ensure!(
+        p.new_oracle != st.client
+            && p.new_oracle != st.freelancer
+            && !st.is_oracle(&p.new_oracle)
+            && !st.is_admin(&p.new_oracle),
+        ContractError::InvalidRole
+    );
+
+    st.oracle_active = true;
+    if let Some(bond) = st.oracle_bonds.remove_and_get(&old_oracle) {
+        let _ = st.oracle_bonds.insert(p.new_oracle, bond);
+    }
+    for o in st.oracles.iter_mut() {
+        if *o == old_oracle {
+            *o = p.new_oracle;
+        }
+    }
+    // Per-milestone assignments and pending approvals follow the rotated seat,
+    // so the new account cannot approve a second time for the same seat.
+    for (_, mut m) in st.milestones.iter_mut() {
+        if m.assigned_oracle == Some(old_oracle) {
+            m.assigned_oracle = Some(p.new_oracle);
+        }
+        if !m.released {
+            for a in m.oracle_approvals.iter_mut() {
+                if *a == old_oracle {
+                    *a = p.new_oracle;
+                }
+            }
+        }
+    }
+
+    // Emit OracleChangedEvent.
+    let ev = OracleChangedEvent {
+        project_id: st.project_id.clone(),
+        old_oracle,
+        new_oracle: p.new_oracle,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- renounceOracle (CLIENT -> disable compromised oracles) ------------------
+
+/// Client-only: disable every oracle seat, e.g. when an oracle key leaked and
+/// no rotation target is safe yet. Oracle-only entrypoints reject with
+/// `Unauthorized` until the client reseats one via `setOracle`.
+#[receive(
+    contract = "paylog",
+    name = "renounceOracle",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn renounce_oracle(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can renounce.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    let st = host.state_mut();
+    st.oracle_active = false;
+
+    // Emit OracleRenouncedEvent.
+    let ev = OracleRenouncedEvent {
+        project_id: st.project_id.clone(),
+        oracles: st.oracles.clone(),
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- transferClientRole (CLIENT -> project handoff) --------------------------
+
+/// Params for `transferClientRole`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct TransferClientParam {
+    pub new_client: AccountAddress, // must not be the freelancer or an oracle
+}
+
+/// Client-only: reassign the payer role. Milestone state is left untouched,
+/// so pending requests can be confirmed by the new client.
+#[receive(
+    contract = "paylog",
+    name = "transferClientRole",
+    parameter = "TransferClientParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn transfer_client_role(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the current client account can hand over.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: TransferClientParam = ctx.parameter_cursor().get()?;
+
+    // Roles must stay distinct.
+    let st = host.state_mut();
+    ensure!(
+        p.new_client != st.freelancer
+            && !st.is_oracle(&p.new_client)
+            && !st.is_admin(&p.new_client),
+        ContractError::InvalidRole
+    );
+
+    let old_client = st.client;
+    st.client = p.new_client;
+
+    // Emit ClientChangedEvent.
+    let ev = ClientChangedEvent {
+        project_id: st.project_id.clone(),
+        old_client,
+        new_client: p.new_client,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- openDispute / resolveDispute (CLIENT|FREELANCER -> ORACLE) --------------
+
+/// Params for `openDispute`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OpenDisputeParam {
+    pub milestone_id: MilestoneId, // must be requested, not released
+    pub reason: ReasonCode,        // category of the complaint
+    pub reason_hash: Hash32,       // digest of the off-chain dispute statement
+}
+
+/// Client- or freelancer-only: flag a requested milestone as disputed.
+#[receive(
+    contract = "paylog",
+    name = "openDispute",
+    parameter = "OpenDisputeParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn open_dispute(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Either paying party may dispute.
+    let sender = require_account_sender(ctx)?;
+    ensure!(
+        sender == host.state().client || sender == host.state().freelancer,
+        ContractError::Unauthorized
+    );
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: OpenDisputeParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Only a live, requested-but-unpaid milestone can be disputed.
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(ms.requested, ContractError::NotRequested);
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.disputed, ContractError::Disputed);
+
+    ms.disputed = true;
+
+    // Emit DisputeOpenedEvent.
+    let ev = DisputeOpenedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        opened_by: sender,
+        reason: p.reason,
+        reason_hash: p.reason_hash,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+/// Params for `resolveDispute`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ResolveDisputeParam {
+    pub milestone_id: MilestoneId, // must be disputed
+    pub uphold: bool,              // false -> milestone is cancelled
+}
+
+/// Oracle-only: clear a dispute; rejecting the request also cancels the milestone.
+#[receive(
+    contract = "paylog",
+    name = "resolveDispute",
+    parameter = "ResolveDisputeParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn resolve_dispute(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Enforce oracle-only access.
+    let sender = require_account_sender(ctx)?;
+    require_oracle(host.state(), sender)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: ResolveDisputeParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ensure!(ms.disputed, ContractError::NotDisputed);
+
+    ms.disputed = false;
+    if !p.uphold {
+        ms.cancelled = true;
+    }
+
+    // Emit DisputeResolvedEvent.
+    let ev = DisputeResolvedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        uphold: p.uphold,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- reopenMilestone (ORACLE -> undo erroneous request) ----------------------
+
+/// Params for `reopenMilestone`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ReopenParam {
+    pub milestone_id: MilestoneId, // must be requested, not released
+}
+
+/// Oracle-only: roll a requested (or partially approved) unpaid milestone back
+/// to its initial state.
+#[receive(
+    contract = "paylog",
+    name = "reopenMilestone",
+    parameter = "ReopenParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn reopen_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Enforce oracle-only access.
+    let sender = require_account_sender(ctx)?;
+    require_oracle(host.state(), sender)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: ReopenParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Paid milestones are final; disputes must be resolved first.
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    // Pending (below-threshold) approvals can be revoked too.
+    ensure!(
+        ms.requested || !ms.oracle_approvals.is_empty(),
+        ContractError::NotRequested
+    );
+    ensure!(!ms.disputed, ContractError::Disputed);
+    // The client's full payment confirmed this work hash; it is final now.
+    ensure!(!ms.client_confirmed, ContractError::AwaitingCosign);
+
+    let revoked_work_hash = ms.work_hash.expect("work_hash set at first approval");
+
+    // Back to initial values.
+    ms.requested = false;
+    ms.work_hash = None;
+    ms.work_hashes.clear();
+    ms.oracle_approvals.clear();
+    ms.acknowledged = false;
+    ms.requested_at_ms = None;
+    ms.oracle_cosigned = false;
+    ms.request_nonce = ms.request_nonce.wrapping_add(1);
+
+    // Emit RequestRevokedEvent.
+    let ev = RequestRevokedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        revoked_work_hash,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- updateWorkHash (ORACLE -> re-verified artifact) -------------------------
+
+/// Params for `updateWorkHash`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct UpdateWorkHashParam {
+    pub milestone_id: MilestoneId, // must be requested, not released
+    pub work_hash: Hash32,         // replaces the stored hash (and artifact list)
+}
+
+/// Oracle-only: replace the work hash of a requested milestone after
+/// re-verification, refreshing `requested_at_ms`. Only available when a single
+/// approval suffices (`threshold == 1`, or the caller is the milestone's
+/// assigned oracle); a quorum's agreed hash cannot be overridden by one oracle,
+/// so use `reopenMilestone` there instead.
+/// The freelancer's acknowledgement and any oracle co-sign referred to the old
+/// artifact and are cleared.
+#[receive(
+    contract = "paylog",
+    name = "updateWorkHash",
+    parameter = "UpdateWorkHashParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn update_work_hash(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Enforce oracle-only access, and only without a quorum.
+    let sender = require_account_sender(ctx)?;
+    require_oracle(host.state(), sender)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+    let threshold = host.state().threshold;
+
+    // Parse params.
+    let p: UpdateWorkHashParam = ctx.parameter_cursor().get()?;
+    ensure!(p.work_hash != [0u8; 32], ContractError::InvalidWorkHash);
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+    let now = ctx.metadata().block_time();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(ms.requested, ContractError::NotRequested);
+    ensure!(!ms.disputed, ContractError::Disputed);
+    // The client's full payment confirmed this work hash; it is final now.
+    ensure!(!ms.client_confirmed, ContractError::AwaitingCosign);
+    let sole_verifier = match ms.assigned_oracle {
+        Some(assigned) => assigned == sender,
+        None => threshold == 1,
+    };
+    ensure!(sole_verifier, ContractError::Unauthorized);
+
+    let old_hash = ms.work_hash.expect("work_hash set at request");
+    ms.work_hash = Some(p.work_hash);
+    ms.work_hashes = vec![p.work_hash];
+    ms.oracle_approvals = vec![sender];
+    ms.acknowledged = false;
+    ms.oracle_cosigned = false;
+    ms.requested_at_ms = Some(now);
+
+    // Emit WorkHashUpdatedEvent.
+    let ev = WorkHashUpdatedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        old_hash,
+        new_hash: p.work_hash,
+        block_time_ms: now,
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- setPaused (CLIENT or ADMIN -> freeze request/confirm) -------------------
+
+/// Params for `setPaused`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct SetPausedParam {
+    pub paused: bool,
+}
+
+/// Client- or admin-only: pause or unpause `requestRelease` and `confirmPayment`.
+/// Views stay available while paused.
+#[receive(
+    contract = "paylog",
+    name = "setPaused",
+    parameter = "SetPausedParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn set_paused(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client or admin account can pause.
+    let sender = require_account_sender(ctx)?;
+    ensure!(
+        sender == host.state().client || host.state().is_admin(&sender),
+        ContractError::Unauthorized
+    );
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: SetPausedParam = ctx.parameter_cursor().get()?;
+
+    let st = host.state_mut();
+    st.paused = p.paused;
+
+    // Emit PausedEvent.
+    let ev = PausedEvent {
+        project_id: st.project_id.clone(),
+        paused: p.paused,
+        block_time_ms: ctx.metadata().block_time(),
+        actor: sender,
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- lockMilestone / unlockMilestone (CLIENT or ADMIN -> freeze one) ---------
+
+/// Params for `lockMilestone` and `unlockMilestone`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct LockMilestoneParam {
+    pub milestone_id: MilestoneId,
+}
+
+/// Client- or admin-only: freeze one milestone (request and confirm reject
+/// with `MilestoneLocked`) while the rest of the project carries on.
+#[receive(
+    contract = "paylog",
+    name = "lockMilestone",
+    parameter = "LockMilestoneParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn lock_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    set_milestone_locked(ctx, host, logger, true)
+}
+
+/// Client- or admin-only: lift a `lockMilestone`.
+#[receive(
+    contract = "paylog",
+    name = "unlockMilestone",
+    parameter = "LockMilestoneParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn unlock_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    set_milestone_locked(ctx, host, logger, false)
+}
+
+/// Shared body of `lockMilestone` / `unlockMilestone`.
+fn set_milestone_locked(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    locked: bool,
+) -> Result<(), ContractError> {
+    // Only the client or admin account can lock.
+    let sender = require_account_sender(ctx)?;
+    ensure!(
+        sender == host.state().client || host.state().is_admin(&sender),
+        ContractError::Unauthorized
+    );
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: LockMilestoneParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+    ms.locked = locked;
+    drop(ms);
+
+    // Emit MilestoneLockedEvent.
+    let ev = MilestoneLockedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        locked,
+        block_time_ms: ctx.metadata().block_time(),
+        actor: sender,
+        seq: host.state_mut().next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- amendAmount (CLIENT -> scope change before request) ---------------------
+
+/// Params for `amendAmount`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AmendAmountParam {
+    pub milestone_id: MilestoneId, // must be neither requested, released nor partly paid
+    pub new_amount_minor: u128,    // non-zero
+}
+
+/// Client-only: change the agreed amount of a milestone before it is requested
+/// and before any payment was confirmed (partial payments survive a reopen).
+#[receive(
+    contract = "paylog",
+    name = "amendAmount",
+    parameter = "AmendAmountParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn amend_amount(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can amend.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: AmendAmountParam = ctx.parameter_cursor().get()?;
+    ensure!(p.new_amount_minor > 0, ContractError::InvalidMilestone);
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Amounts are locked once the oracle has requested release.
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.requested, ContractError::AlreadyRequested);
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    // Payments were made against the old amount; a lower one could leave the
+    // milestone overpaid and unconfirmable.
+    ensure!(ms.paid_so_far_minor == 0, ContractError::AmountMismatch);
+
+    let old_amount_minor = ms.amount_minor;
+    ms.amount_minor = p.new_amount_minor;
+
+    // Emit AmountAmendedEvent.
+    let ev = AmountAmendedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        old_amount_minor,
+        new_amount_minor: p.new_amount_minor,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- extendDeadline (CLIENT -> more time for the work) -----------------------
+
+/// Params for `extendDeadline`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ExtendDeadlineParam {
+    pub milestone_id: MilestoneId, // must have a deadline and not be released
+    pub new_deadline_ms: Timestamp, // strictly later than the current deadline
+}
+
+/// Client-only: push a milestone deadline later. Milestones without a deadline
+/// are already unbounded, so they reject with `DeadlineNotLater`.
+#[receive(
+    contract = "paylog",
+    name = "extendDeadline",
+    parameter = "ExtendDeadlineParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn extend_deadline(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can extend.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: ExtendDeadlineParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    let old_deadline_ms = ms.deadline_ms.ok_or(ContractError::DeadlineNotLater)?;
+    ensure!(
+        p.new_deadline_ms > old_deadline_ms,
+        ContractError::DeadlineNotLater
+    );
+
+    ms.deadline_ms = Some(p.new_deadline_ms);
+
+    // Emit DeadlineExtendedEvent.
+    let ev = DeadlineExtendedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        old_deadline_ms,
+        new_deadline_ms: p.new_deadline_ms,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- addMilestone (CLIENT -> grow the project) -------------------------------
+
+/// Params for `addMilestone`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AddMilestoneParam {
+    pub amount_minor: u128, // non-zero
+}
+
+/// Client-only: append a fresh milestone and return its id.
+/// Ids are assigned from `State.milestone_count`, so the new milestone gets the
+/// next key in the `milestones` map and existing ids never shift.
+#[receive(
+    contract = "paylog",
+    name = "addMilestone",
+    parameter = "AddMilestoneParam",
+    return_value = "MilestoneId",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn add_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<MilestoneId, ContractError> {
+    // Only the client account can add milestones.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: AddMilestoneParam = ctx.parameter_cursor().get()?;
+    ensure!(p.amount_minor > 0, ContractError::InvalidMilestone);
+
+    let st = host.state_mut();
+    let milestone_id = st.milestone_count;
+    ensure!(
+        (milestone_id as usize) < MAX_MILESTONES,
+        ContractError::TooManyMilestones
+    );
+    st.milestone_count = milestone_id.checked_add(1).ok_or(ContractError::Overflow)?;
+    // Ids are never reused; an occupied slot means the counter is corrupt.
+    let previous = st.milestones.insert(
+        milestone_id,
+        Milestone::new(p.amount_minor, None, st.plt_decimals),
+    );
+    ensure!(previous.is_none(), ContractError::InvalidMilestone);
+
+    // Emit MilestoneAddedEvent.
+    let ev = MilestoneAddedEvent {
+        project_id: st.project_id.clone(),
+        milestone_id,
+        amount_minor: p.amount_minor,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(milestone_id)
+}
+
+// ---- freelancerAcknowledge (FREELANCER -> mutual consent) --------------------
+
+/// Params for `freelancerAcknowledge`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AcknowledgeParam {
+    pub milestone_id: MilestoneId, // must be requested, not released
+}
+
+/// Freelancer-only: agree that a requested milestone is complete.
+#[receive(
+    contract = "paylog",
+    name = "freelancerAcknowledge",
+    parameter = "AcknowledgeParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn freelancer_acknowledge(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the freelancer account can acknowledge.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().freelancer)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: AcknowledgeParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(ms.requested, ContractError::NotRequested);
+    ensure!(!ms.released, ContractError::AlreadyReleased);
+
+    ms.acknowledged = true;
+
+    // Emit AcknowledgedEvent.
+    let ev = AcknowledgedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- recordRefund (CLIENT -> reversal after release) -------------------------
+
+/// Params for `recordRefund`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct RefundParam {
+    pub milestone_id: MilestoneId, // must be released
+    pub refund_tx_hash: TxHash,    // 32-byte PLT refund transfer hash
+}
+
+/// Client-only: attest that a released milestone was refunded off-chain.
+#[receive(
+    contract = "paylog",
+    name = "recordRefund",
+    parameter = "RefundParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn record_refund(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can record refunds.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: RefundParam = ctx.parameter_cursor().get()?;
+
+    // The refund transfer must be real and not one already recorded.
+    ensure!(p.refund_tx_hash != [0u8; 32], ContractError::InvalidTxHash);
+    ensure!(
+        host.state()
+            .tx_hash_free_for(&p.refund_tx_hash, p.milestone_id),
+        ContractError::DuplicateTxHash
+    );
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ensure!(ms.released, ContractError::NotReleased);
+    ensure!(!ms.refunded, ContractError::AlreadyRefunded);
+
+    ms.refunded = true;
+    ms.refund_tx_hash = Some(p.refund_tx_hash);
+    let amount_minor = ms.amount_minor;
+    drop(ms);
+
+    // Index the tx hash for duplicate detection and `findByTxHash`.
+    let _ = host
+        .state_mut()
+        .tx_hash_index
+        .insert(p.refund_tx_hash, p.milestone_id);
+
+    // Emit RefundedEvent.
+    let ev = RefundedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        refund_tx_hash: p.refund_tx_hash,
+        amount_minor,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- submitReceipt (FREELANCER -> acknowledge payment) -----------------------
+
+/// Params for `submitReceipt`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ReceiptParam {
+    pub milestone_id: MilestoneId, // must be released
+    pub receipt_sig: [u8; 64],     // ed25519 signature over the final PLT tx hash
+}
+
+/// Freelancer-only: store a signed receipt for a released milestone. The
+/// signature must verify over the final PLT tx hash under one of the
+/// freelancer account's keys, so it also stands on its own off-chain.
+#[receive(
+    contract = "paylog",
+    name = "submitReceipt",
+    parameter = "ReceiptParam",
+    error = "ContractError",
+    mutable,
+    enable_logger,
+    crypto_primitives
+)]
+fn submit_receipt(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+    crypto_primitives: &CryptoPrimitives,
+) -> Result<(), ContractError> {
+    // Only the freelancer account can submit receipts.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().freelancer)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: ReceiptParam = ctx.parameter_cursor().get()?;
+
+    // Look up the signed message before borrowing state_mut.
+    let plt_tx_hash = {
+        let ms = host
+            .state()
+            .milestones
+            .get(&p.milestone_id)
+            .ok_or(ContractError::InvalidMilestone)?;
+        ensure!(ms.released, ContractError::NotReleased);
+        ensure!(ms.receipt_sig.is_none(), ContractError::AlreadyReceipted);
+        *ms.plt_tx_hashes
+            .last()
+            .expect("released milestones have a payment")
+    };
+
+    // The receipt must verify under one of the freelancer's account keys.
+    let keys = host
+        .account_public_keys(sender)
+        .map_err(|_| ContractError::InvalidSignature)?;
+    let signature = SignatureEd25519(p.receipt_sig);
+    let verified = keys.keys.values().any(|c| {
+        c.keys.values().any(|k| match k {
+            PublicKey::Ed25519(pk) => {
+                crypto_primitives.verify_ed25519_signature(*pk, signature, &plt_tx_hash)
+            }
+        })
+    });
+    ensure!(verified, ContractError::InvalidSignature);
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Record the receipt.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+    ms.receipt_sig = Some(p.receipt_sig);
+    drop(ms);
+
+    // Emit ReceiptSubmittedEvent.
+    let ev = ReceiptSubmittedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        plt_tx_hash,
+        receipt_sig: p.receipt_sig,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- recordDeposit (CLIENT -> earmark funds before request) ------------------
+
+/// Params for `recordDeposit`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct DepositParam {
+    pub milestone_id: MilestoneId, // must not be requested yet
+    pub deposit_tx_hash: TxHash,   // 32-byte PLT earmarking transfer hash
+}
+
+/// Client-only: attest that funds for a milestone were set aside off-chain.
+/// Purely informational; the contract still holds no tokens.
+#[receive(
+    contract = "paylog",
+    name = "recordDeposit",
+    parameter = "DepositParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn record_deposit(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can record deposits.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: DepositParam = ctx.parameter_cursor().get()?;
+
+    // The deposit transfer must be real and not one already recorded.
+    ensure!(p.deposit_tx_hash != [0u8; 32], ContractError::InvalidTxHash);
+    ensure!(
+        host.state()
+            .tx_hash_free_for(&p.deposit_tx_hash, p.milestone_id),
+        ContractError::DuplicateTxHash
+    );
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Deposits are only meaningful before the work is verified.
+    ensure!(!ms.cancelled, ContractError::Cancelled);
+    ensure!(!ms.requested, ContractError::AlreadyRequested);
+    ensure!(
+        ms.deposit_tx_hash.is_none(),
+        ContractError::AlreadyDeposited
+    );
+
+    ms.deposit_tx_hash = Some(p.deposit_tx_hash);
+    let amount_minor = ms.amount_minor;
+    drop(ms);
+
+    // Index the tx hash for duplicate detection and `findByTxHash`.
+    let _ = host
+        .state_mut()
+        .tx_hash_index
+        .insert(p.deposit_tx_hash, p.milestone_id);
+
+    // Emit DepositRecordedEvent.
+    let ev = DepositRecordedEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        deposit_tx_hash: p.deposit_tx_hash,
+        amount_minor,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- recordOracleBond (ORACLE -> accountability reference) -------------------
+
+/// Params for `recordOracleBond`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct OracleBondParam {
+    pub bond_tx_hash: TxHash, // 32-byte tx that posted the bond
+}
+
+/// Oracle-only: attest the bond the caller posted off-chain, so false
+/// verifications can be traced to it. Replaces the caller's earlier record (the
+/// events keep the history); the contract never holds or slashes the bond.
+#[receive(
+    contract = "paylog",
+    name = "recordOracleBond",
+    parameter = "OracleBondParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn record_oracle_bond(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only an oracle account can record its bond.
+    let sender = require_account_sender(ctx)?;
+    require_oracle(host.state(), sender)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: OracleBondParam = ctx.parameter_cursor().get()?;
+
+    // The bond transfer must be real and not one already recorded.
+    ensure!(p.bond_tx_hash != [0u8; 32], ContractError::InvalidTxHash);
+    ensure!(
+        !host.state().tx_hash_recorded(&p.bond_tx_hash),
+        ContractError::DuplicateTxHash
+    );
+
+    // Keyed by seat; replaced bonds stay indexed so they can never be reused.
+    let st = host.state_mut();
+    let _ = st.oracle_bonds.insert(sender, p.bond_tx_hash);
+    let _ = st.bond_tx_index.insert(p.bond_tx_hash, sender);
+
+    // Emit OracleBondRecordedEvent.
+    let ev = OracleBondRecordedEvent {
+        project_id: st.project_id.clone(),
+        oracle: sender,
+        bond_tx_hash: p.bond_tx_hash,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- setRefId (CLIENT -> link to off-chain records) --------------------------
+
+/// Params for `setRefId`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct SetRefIdParam {
+    pub milestone_id: MilestoneId,
+    pub ref_id: Option<String>, // None or "" clears it; at most MAX_REF_ID_LEN bytes
+}
+
+/// Client-only: set or clear the off-chain reference id of a milestone.
+#[receive(
+    contract = "paylog",
+    name = "setRefId",
+    parameter = "SetRefIdParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn set_ref_id(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can set reference ids.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: SetRefIdParam = ctx.parameter_cursor().get()?;
+    let ref_id = p.ref_id.filter(|id| !id.is_empty());
+    if let Some(id) = &ref_id {
+        ensure!(id.len() <= MAX_REF_ID_LEN, ContractError::ParseError);
+    }
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ms.ref_id = ref_id.clone();
+
+    // Emit RefIdSetEvent.
+    let ev = RefIdSetEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        ref_id,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- setLabel (CLIENT -> categorize a milestone) -----------------------------
+
+/// Params for `setLabel`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct SetLabelParam {
+    pub milestone_id: MilestoneId,
+    pub label: Option<String>, // None or "" clears it; at most MAX_LABEL_LEN bytes
+}
+
+/// Client-only: set or clear the category label of a milestone.
+#[receive(
+    contract = "paylog",
+    name = "setLabel",
+    parameter = "SetLabelParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn set_label(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can label milestones.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: SetLabelParam = ctx.parameter_cursor().get()?;
+    let label = p.label.filter(|l| !l.is_empty());
+    if let Some(l) = &label {
+        ensure!(l.len() <= MAX_LABEL_LEN, ContractError::ParseError);
+    }
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    ms.label = label.clone();
+
+    // Emit LabelSetEvent.
+    let ev = LabelSetEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        label,
+        block_time_ms: ctx.metadata().block_time(),
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- resetMilestone (ADMIN or CLIENT -> back to pristine) --------------------
+
+/// Params for `resetMilestone`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ResetParam {
+    pub milestone_id: MilestoneId,
+    pub force: bool, // required to reset a released milestone
+}
+
+/// Admin-only (client-only when no admin is configured): clear every flag,
+/// hash and timestamp of a milestone. `amount_minor`, `deadline_ms`, `ref_id`,
+/// `label`, `assigned_oracle`, `depends_on` and `plt_decimals` are
+/// configuration and survive; `request_nonce` is bumped. Idempotency keys are
+/// dropped so they can be used again. Recorded tx hashes stay reserved for
+/// this milestone: they can be recorded on it again, never on another one.
+#[receive(
+    contract = "paylog",
+    name = "resetMilestone",
+    parameter = "ResetParam",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn reset_milestone(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // The admin resets; the client stands in when there is no admin.
+    let sender = require_account_sender(ctx)?;
+    let st = host.state();
+    let authorized = match st.admin {
+        Some(admin) => sender == admin,
+        None => sender == st.client,
+    };
+    ensure!(authorized, ContractError::Unauthorized);
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Parse params.
+    let p: ResetParam = ctx.parameter_cursor().get()?;
+
+    let project_id = host.state().project_id.clone();
+    let seq = host.state_mut().next_seq();
+
+    // Fetch milestone.
+    let mut ms = host
+        .state_mut()
+        .milestones
+        .get_mut(&p.milestone_id)
+        .ok_or(ContractError::InvalidMilestone)?;
+
+    // Wiping a paid milestone erases its attestation; require explicit force.
+    let was_released = ms.released;
+    ensure!(!was_released || p.force, ContractError::AlreadyReleased);
+
+    let keys = ms.idempotency_keys.clone();
+
+    let mut fresh = Milestone::new(ms.amount_minor, ms.deadline_ms, ms.plt_decimals);
+    fresh.ref_id = ms.ref_id.clone();
+    fresh.label = ms.label.clone();
+    fresh.assigned_oracle = ms.assigned_oracle;
+    fresh.depends_on = ms.depends_on.clone();
+    fresh.request_nonce = ms.request_nonce.wrapping_add(1);
+    *ms = fresh;
+    drop(ms);
+
+    // Old idempotency keys must not turn a fresh confirm into a silent no-op.
+    for key in keys.iter() {
+        host.state_mut().idempotency_keys.remove(key);
+    }
+
+    // Emit MilestoneResetEvent.
+    let ev = MilestoneResetEvent {
+        project_id,
+        milestone_id: p.milestone_id,
+        was_released,
+        block_time_ms: ctx.metadata().block_time(),
+        actor: sender,
+        seq,
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- closeProject (CLIENT -> terminal state) ---------------------------------
+
+/// Client-only: mark the project closed once every milestone is released or
+/// cancelled. Irreversible; every mutating entrypoint rejects afterwards.
+#[receive(
+    contract = "paylog",
+    name = "closeProject",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn close_project(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can close.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    // Every milestone must be settled one way or the other.
+    let mut released_count: u32 = 0;
+    let mut cancelled_count: u32 = 0;
+    for (_, m) in host.state().milestones.iter() {
+        if m.released {
+            released_count += 1;
+        } else if m.cancelled {
+            cancelled_count += 1;
+        } else {
+            return Err(ContractError::MilestonesOutstanding);
+        }
+    }
+
+    let st = host.state_mut();
+    st.closed = true;
+
+    // Emit ProjectClosedEvent.
+    let ev = ProjectClosedEvent {
+        project_id: st.project_id.clone(),
+        released_count,
+        cancelled_count,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- cancelProject (CLIENT -> walk away) -------------------------------------
+
+/// Client-only: cancel every milestone that is not released yet and close the
+/// project. Released milestones are left untouched.
+#[receive(
+    contract = "paylog",
+    name = "cancelProject",
+    error = "ContractError",
+    mutable,
+    enable_logger
+)]
+fn cancel_project(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> Result<(), ContractError> {
+    // Only the client account can cancel the project.
+    let sender = require_account_sender(ctx)?;
+    require_role(sender, host.state().client)?;
+    ensure!(!host.state().closed, ContractError::Closed);
+
+    let st = host.state_mut();
+    let mut cancelled_count: u32 = 0;
+    for (_, mut m) in st.milestones.iter_mut() {
+        if !m.released && !m.cancelled {
+            m.cancelled = true;
+            cancelled_count += 1;
+        }
+    }
+    st.closed = true;
+
+    // Emit ProjectCancelledEvent.
+    let ev = ProjectCancelledEvent {
+        project_id: st.project_id.clone(),
+        cancelled_count,
+        block_time_ms: ctx.metadata().block_time(),
+        seq: st.next_seq(),
+    };
+    logger.log(&ev)?;
+
+    Ok(())
+}
+
+// ---- Read-only view ----------------------------------------------------------
+
+/// Input for `viewMilestone`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ViewParam {
     pub milestone_id: MilestoneId,
 }
 
 /// Return model for `viewMilestone`.
 #[derive(Serial, Deserial, SchemaType, Clone)]
-pub struct MilestoneView {
+pub struct MilestoneView {
+    pub amount_minor: u128,
+    pub requested: bool,
+    pub released: bool,
+    pub work_hash: Option<Hash32>,
+    pub plt_tx_hashes: Vec<TxHash>,
+    pub paid_so_far_minor: u128,
+    pub requested_at_ms: Option<Timestamp>,
+    pub attested_at_ms: Option<Timestamp>,
+    pub cancelled: bool,
+    pub deadline_ms: Option<Timestamp>,
+    pub disputed: bool,
+    pub plt_token_id: Option<String>,
+    pub memo: Option<String>,
+    pub work_commitment: Option<Hash32>,
+    pub acknowledged: bool,
+    pub refunded: bool,
+    pub refund_tx_hash: Option<TxHash>,
+    pub deposit_tx_hash: Option<TxHash>,
+    pub work_hashes: Vec<Hash32>,
+    pub ref_id: Option<String>,
+    pub payer: Option<AccountAddress>,
+    pub payee: Option<AccountAddress>,
+    pub assigned_oracle: Option<AccountAddress>,
+    pub label: Option<String>,
+    pub receipt_sig: Option<[u8; 64]>,
+    pub depends_on: Vec<MilestoneId>,
+    pub locked: bool,
+    pub client_confirmed: bool,
+    pub oracle_cosigned: bool,
+    pub plt_decimals: u8,
+    pub request_nonce: u32,
+}
+
+impl From<&Milestone> for MilestoneView {
+    fn from(m: &Milestone) -> Self {
+        MilestoneView {
+            amount_minor: m.amount_minor,
+            requested: m.requested,
+            released: m.released,
+            work_hash: m.work_hash,
+            plt_tx_hashes: m.plt_tx_hashes.clone(),
+            paid_so_far_minor: m.paid_so_far_minor,
+            requested_at_ms: m.requested_at_ms,
+            attested_at_ms: m.attested_at_ms,
+            cancelled: m.cancelled,
+            deadline_ms: m.deadline_ms,
+            disputed: m.disputed,
+            plt_token_id: m.plt_token_id.clone(),
+            memo: m.memo.clone(),
+            work_commitment: m.work_commitment,
+            acknowledged: m.acknowledged,
+            refunded: m.refunded,
+            refund_tx_hash: m.refund_tx_hash,
+            deposit_tx_hash: m.deposit_tx_hash,
+            work_hashes: m.work_hashes.clone(),
+            ref_id: m.ref_id.clone(),
+            payer: m.payer,
+            payee: m.payee,
+            assigned_oracle: m.assigned_oracle,
+            label: m.label.clone(),
+            receipt_sig: m.receipt_sig,
+            depends_on: m.depends_on.clone(),
+            locked: m.locked,
+            client_confirmed: m.client_confirmed,
+            oracle_cosigned: m.oracle_cosigned,
+            plt_decimals: m.plt_decimals,
+            request_nonce: m.request_nonce,
+        }
+    }
+}
+
+/// Returns the milestone state (or `None` if out of range).
+#[receive(
+    contract = "paylog",
+    name = "viewMilestone",
+    parameter = "ViewParam",
+    return_value = "Option<MilestoneView>"
+)]
+fn view_milestone(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Option<MilestoneView>> {
+    let p: ViewParam = ctx.parameter_cursor().get()?;
+    let maybe = host.state().milestones.get(&p.milestone_id);
+    Ok(maybe.map(|m| MilestoneView::from(&*m)))
+}
+
+/// Compact milestone status: `(flags, amount_minor)`, where `flags` packs
+/// bit0 = requested, bit1 = released, bit2 = cancelled, bit3 = disputed
+/// (higher bits are 0).
+pub type CompactMilestone = (u8, u128);
+
+/// Returns the milestone as `CompactMilestone` (or `None` if out of range), for
+/// callers that only need the flags and amount, e.g. other contracts.
+#[receive(
+    contract = "paylog",
+    name = "viewMilestoneCompact",
+    parameter = "ViewParam",
+    return_value = "Option<CompactMilestone>"
+)]
+fn view_milestone_compact(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Option<CompactMilestone>> {
+    let p: ViewParam = ctx.parameter_cursor().get()?;
+    let maybe = host.state().milestones.get(&p.milestone_id);
+    Ok(maybe.map(|m| {
+        let flags = (m.requested as u8)
+            | ((m.released as u8) << 1)
+            | ((m.cancelled as u8) << 2)
+            | ((m.disputed as u8) << 3);
+        (flags, m.amount_minor)
+    }))
+}
+
+/// Returns every milestone in id order (position in the vector == milestone id).
+#[receive(
+    contract = "paylog",
+    name = "viewAllMilestones",
+    return_value = "Vec<MilestoneView>"
+)]
+fn view_all_milestones(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<MilestoneView>> {
+    let st = host.state();
+    Ok((0..st.milestone_count)
+        .filter_map(|id| st.milestones.get(&id))
+        .map(|m| MilestoneView::from(&*m))
+        .collect())
+}
+
+/// One row of `viewAuditTrail`: only the proof artifacts and their timestamps.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AuditEntry {
+    pub milestone_id: MilestoneId,
+    pub work_hash: Option<Hash32>,
+    pub plt_tx_hashes: Vec<TxHash>, // all payments, in order (partials included)
+    pub requested_at_ms: Option<Timestamp>,
+    pub attested_at_ms: Option<Timestamp>,
+}
+
+/// Returns the proof trail of every milestone in id order, omitting amounts
+/// and flags to keep the payload small for verification tooling.
+#[receive(
+    contract = "paylog",
+    name = "viewAuditTrail",
+    return_value = "Vec<AuditEntry>"
+)]
+fn view_audit_trail(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Vec<AuditEntry>> {
+    let st = host.state();
+    Ok((0..st.milestone_count)
+        .filter_map(|id| {
+            st.milestones.get(&id).map(|m| AuditEntry {
+                milestone_id: id,
+                work_hash: m.work_hash,
+                plt_tx_hashes: m.plt_tx_hashes.clone(),
+                requested_at_ms: m.requested_at_ms,
+                attested_at_ms: m.attested_at_ms,
+            })
+        })
+        .collect())
+}
+
+/// Coarse lifecycle bucket derived from the `requested`/`released` flags.
+#[derive(Serial, Deserial, SchemaType, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,   // not yet requested
+    Requested, // requested, awaiting payment
+    Released,  // paid and attested
+}
+
+/// Input for `viewMilestonesByStatus`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct StatusParam {
+    pub status: MilestoneStatus,
+}
+
+/// Returns `(id, view)` pairs, in id order, for milestones in the given status.
+#[receive(
+    contract = "paylog",
+    name = "viewMilestonesByStatus",
+    parameter = "StatusParam",
+    return_value = "Vec<(MilestoneId, MilestoneView)>"
+)]
+fn view_milestones_by_status(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<(MilestoneId, MilestoneView)>> {
+    let p: StatusParam = ctx.parameter_cursor().get()?;
+    let st = host.state();
+    Ok((0..st.milestone_count)
+        .filter_map(|id| st.milestones.get(&id).map(|m| (id, m)))
+        .filter(|(_, m)| m.status() == Some(p.status))
+        .map(|(id, m)| (id, MilestoneView::from(&*m)))
+        .collect())
+}
+
+/// Returns the lowest-id milestone awaiting payment (requested, not released,
+/// not cancelled), or `None` if nothing is payable right now.
+#[receive(
+    contract = "paylog",
+    name = "viewFirstUnpaidMilestone",
+    return_value = "Option<(MilestoneId, MilestoneView)>"
+)]
+fn view_first_unpaid_milestone(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Option<(MilestoneId, MilestoneView)>> {
+    let st = host.state();
+    Ok((0..st.milestone_count)
+        .filter_map(|id| st.milestones.get(&id).map(|m| (id, m)))
+        .find(|(_, m)| m.status() == Some(MilestoneStatus::Requested))
+        .map(|(id, m)| (id, MilestoneView::from(&*m))))
+}
+
+/// Max ids accepted by `bulkView`.
+const MAX_BULK_VIEW: usize = 256;
+
+/// Input for `bulkView`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct BulkViewParam {
+    pub ids: Vec<MilestoneId>, // at most MAX_BULK_VIEW entries
+}
+
+/// Returns one entry per requested id, in input order; unknown ids map to `None`.
+#[receive(
+    contract = "paylog",
+    name = "bulkView",
+    parameter = "BulkViewParam",
+    error = "ContractError",
+    return_value = "Vec<Option<MilestoneView>>"
+)]
+fn bulk_view(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> Result<Vec<Option<MilestoneView>>, ContractError> {
+    let p: BulkViewParam = ctx.parameter_cursor().get()?;
+    ensure!(p.ids.len() <= MAX_BULK_VIEW, ContractError::ParseError);
+    let st = host.state();
+    Ok(p.ids
+        .iter()
+        .map(|id| st.milestones.get(id).map(|m| MilestoneView::from(&*m)))
+        .collect())
+}
+
+/// Max milestones returned by one `viewMilestonesPage` call; larger limits are clamped.
+const MAX_PAGE_LEN: u32 = 100;
+
+/// Input for `viewMilestonesPage`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct PageParam {
+    pub offset: u32, // first milestone id of the page
+    pub limit: u32,  // clamped to MAX_PAGE_LEN
+}
+
+/// Returns `(id, view)` pairs for ids `offset..offset + limit`, in id order;
+/// empty past the end.
+#[receive(
+    contract = "paylog",
+    name = "viewMilestonesPage",
+    parameter = "PageParam",
+    return_value = "Vec<(MilestoneId, MilestoneView)>"
+)]
+fn view_milestones_page(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<(MilestoneId, MilestoneView)>> {
+    let p: PageParam = ctx.parameter_cursor().get()?;
+    let st = host.state();
+    let end = p
+        .offset
+        .saturating_add(p.limit.min(MAX_PAGE_LEN))
+        .min(st.milestone_count);
+    Ok((p.offset..end)
+        .filter_map(|id| {
+            st.milestones
+                .get(&id)
+                .map(|m| (id, MilestoneView::from(&*m)))
+        })
+        .collect())
+}
+
+/// Return model for `viewProject` (aggregates computed over all milestones).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ProjectSummary {
+    pub project_id: String,
+    pub client: AccountAddress,
+    pub freelancer: AccountAddress,
+    pub oracles: Vec<AccountAddress>,
+    pub threshold: u32,
+    pub plt_decimals: u8,
+    pub currency_symbol: Option<String>,
+    pub milestone_count: u32,
+    pub total_amount_minor: u128,    // sum over all milestones
+    pub released_amount_minor: u128, // sum over released milestones
+    pub requested_count: u32,        // requested (including released)
+    pub released_count: u32,
+    pub request_count: u64, // oracle approvals accepted (all time)
+    pub confirm_count: u64, // client payments accepted (all time)
+    pub oracle_bonds: Vec<(AccountAddress, TxHash)>, // recorded bonds, in seat order
+}
+
+/// Returns a one-call snapshot of participants and milestone totals.
+#[receive(
+    contract = "paylog",
+    name = "viewProject",
+    return_value = "ProjectSummary",
+    error = "ContractError"
+)]
+fn view_project(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> Result<ProjectSummary, ContractError> {
+    let st = host.state();
+
+    let mut total_amount_minor: u128 = 0;
+    let mut released_amount_minor: u128 = 0;
+    let mut requested_count: u32 = 0;
+    let mut released_count: u32 = 0;
+    for (_, m) in st.milestones.iter() {
+        total_amount_minor = add_amount(total_amount_minor, m.amount_minor)?;
+        if m.requested {
+            requested_count += 1;
+        }
+        if m.released {
+            released_amount_minor = add_amount(released_amount_minor, m.amount_minor)?;
+            released_count += 1;
+        }
+    }
+
+    Ok(ProjectSummary {
+        project_id: st.project_id.clone(),
+        client: st.client,
+        freelancer: st.freelancer,
+        oracles: st.oracles.clone(),
+        threshold: st.threshold,
+        plt_decimals: st.plt_decimals,
+        currency_symbol: st.currency_symbol.clone(),
+        milestone_count: st.milestone_count,
+        total_amount_minor,
+        released_amount_minor,
+        requested_count,
+        released_count,
+        request_count: st.request_count,
+        confirm_count: st.confirm_count,
+        oracle_bonds: st.bonds(),
+    })
+}
+
+/// Return model for `viewProgressCounts`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ProgressCounts {
+    pub total: u32,
+    pub requested: u32, // requested (including released)
+    pub released: u32,
+    pub cancelled: u32, // cancelled before release
+}
+
+/// Returns milestone tallies only (e.g. for a "3 of 7 paid" label).
+#[receive(
+    contract = "paylog",
+    name = "viewProgressCounts",
+    return_value = "ProgressCounts"
+)]
+fn view_progress_counts(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<ProgressCounts> {
+    let st = host.state();
+    let mut counts = ProgressCounts {
+        total: st.milestone_count,
+        requested: 0,
+        released: 0,
+        cancelled: 0,
+    };
+    for (_, m) in st.milestones.iter() {
+        counts.requested += m.requested as u32;
+        counts.released += m.released as u32;
+        counts.cancelled += (m.cancelled && !m.released) as u32;
+    }
+    Ok(counts)
+}
+
+/// Returns `released_amount_minor * 100 / total_amount_minor` (0 when the total
+/// is 0), i.e., the share of the budget already paid, rounded down. Exact for
+/// any amounts; only the sums themselves can overflow.
+#[receive(
+    contract = "paylog",
+    name = "viewCompletionPercent",
+    return_value = "u8",
+    error = "ContractError"
+)]
+fn view_completion_percent(_ctx: &ReceiveContext, host: &Host<State>) -> Result<u8, ContractError> {
+    let mut total_amount_minor: u128 = 0;
+    let mut released_amount_minor: u128 = 0;
+    for (_, m) in host.state().milestones.iter() {
+        total_amount_minor = add_amount(total_amount_minor, m.amount_minor)?;
+        if m.released {
+            released_amount_minor = add_amount(released_amount_minor, m.amount_minor)?;
+        }
+    }
+
+    Ok(percent_floor(released_amount_minor, total_amount_minor))
+}
+
+/// Return model for `viewHealthCheck`; every flag is true on a consistent state.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct HealthCheck {
+    pub ok: bool,                              // all invariants below hold
+    pub released_implies_requested: bool,      // no release without a request
+    pub released_implies_hashes_present: bool, // released => work hash and PLT tx hash
+}
+
+/// Checks milestone invariants over the whole state (for monitoring).
+#[receive(
+    contract = "paylog",
+    name = "viewHealthCheck",
+    return_value = "HealthCheck"
+)]
+fn view_health_check(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<HealthCheck> {
+    let mut released_implies_requested = true;
+    let mut released_implies_hashes_present = true;
+    for (_, m) in host.state().milestones.iter() {
+        if !m.released {
+            continue;
+        }
+        released_implies_requested &= m.requested;
+        released_implies_hashes_present &= m.work_hash.is_some() && !m.plt_tx_hashes.is_empty();
+    }
+
+    Ok(HealthCheck {
+        ok: released_implies_requested && released_implies_hashes_present,
+        released_implies_requested,
+        released_implies_hashes_present,
+    })
+}
+
+/// Return model for `viewAmountStats` (all zero when there are no milestones).
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct AmountStats {
+    pub min_minor: u128,
+    pub max_minor: u128,
+    pub total_minor: u128,
+    pub count: u32,
+}
+
+/// Returns min/max/total of milestone amounts for analytics dashboards.
+#[receive(
+    contract = "paylog",
+    name = "viewAmountStats",
+    return_value = "AmountStats",
+    error = "ContractError"
+)]
+fn view_amount_stats(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> Result<AmountStats, ContractError> {
+    let mut stats = AmountStats {
+        min_minor: u128::MAX,
+        max_minor: 0,
+        total_minor: 0,
+        count: 0,
+    };
+    for (_, m) in host.state().milestones.iter() {
+        stats.min_minor = stats.min_minor.min(m.amount_minor);
+        stats.max_minor = stats.max_minor.max(m.amount_minor);
+        stats.total_minor = add_amount(stats.total_minor, m.amount_minor)?;
+        stats.count += 1;
+    }
+
+    // Defensive: init forbids an empty project, but never report u128::MAX.
+    if stats.count == 0 {
+        stats.min_minor = 0;
+    }
+
+    Ok(stats)
+}
+
+/// Return model for `getRoles`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct Roles {
+    pub client: AccountAddress,
+    pub freelancer: AccountAddress,
+    pub oracles: Vec<AccountAddress>,
+    pub admin: Option<AccountAddress>,
+}
+
+/// Returns the participant accounts (no milestone data is loaded).
+#[receive(contract = "paylog", name = "getRoles", return_value = "Roles")]
+fn get_roles(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Roles> {
+    let st = host.state();
+    Ok(Roles {
+        client: st.client,
+        freelancer: st.freelancer,
+        oracles: st.oracles.clone(),
+        admin: st.admin,
+    })
+}
+
+/// Rebuilds an `InitParams` from the current state so tooling can spin up a
+/// similar project. Amounts are the current (possibly amended) values, roles
+/// are the current holders, and per-milestone vectors are `None` when they
+/// cannot be expressed (e.g., only some milestones have a deadline).
+#[receive(
+    contract = "paylog",
+    name = "viewInitParams",
+    return_value = "InitParams"
+)]
+fn view_init_params(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<InitParams> {
+    let st = host.state();
+    let ms: Vec<Milestone> = (0..st.milestone_count)
+        .filter_map(|id| st.milestones.get(&id).map(|m| m.clone()))
+        .collect();
+
+    let deadlines: Option<Vec<Timestamp>> = ms.iter().map(|m| m.deadline_ms).collect();
+    let assigned: Option<Vec<AccountAddress>> = ms.iter().map(|m| m.assigned_oracle).collect();
+    let ref_ids = ms.iter().any(|m| m.ref_id.is_some()).then(|| {
+        ms.iter()
+            .map(|m| m.ref_id.clone().unwrap_or_default())
+            .collect()
+    });
+    let labels = ms.iter().any(|m| m.label.is_some()).then(|| {
+        ms.iter()
+            .map(|m| m.label.clone().unwrap_or_default())
+            .collect()
+    });
+    let depends_on = ms
+        .iter()
+        .any(|m| !m.depends_on.is_empty())
+        .then(|| ms.iter().map(|m| m.depends_on.clone()).collect());
+    let decimals_per_milestone = ms
+        .iter()
+        .any(|m| m.plt_decimals != st.plt_decimals)
+        .then(|| ms.iter().map(|m| m.plt_decimals).collect());
+
+    Ok(InitParams {
+        project_id: st.project_id.clone(),
+        client: st.client,
+        freelancer: st.freelancer,
+        oracles: st.oracles.clone(),
+        threshold: st.threshold,
+        amounts: ms.iter().map(|m| m.amount_minor).collect(),
+        plt_decimals: st.plt_decimals,
+        deadlines_ms: deadlines,
+        require_acknowledge: st.require_acknowledge,
+        amount_tolerance_minor: Some(st.amount_tolerance_minor).filter(|t| *t > 0),
+        sequential: st.sequential,
+        confirm_window_ms: st.confirm_window_ms,
+        ref_ids,
+        labels,
+        currency_symbol: st.currency_symbol.clone(),
+        admin: st.admin,
+        oracle_per_milestone: assigned,
+        strict_ordering: st.strict_ordering,
+        depends_on,
+        require_oracle_cosign: st.require_oracle_cosign,
+        decimals_per_milestone,
+    })
+}
+
+/// Input for `findByTxHash`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct FindByTxHashParam {
+    pub plt_tx_hash: TxHash,
+}
+
+/// Returns the milestone that recorded `plt_tx_hash` (any of its payments), if any.
+#[receive(
+    contract = "paylog",
+    name = "findByTxHash",
+    parameter = "FindByTxHashParam",
+    return_value = "Option<MilestoneId>"
+)]
+fn find_by_tx_hash(ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Option<MilestoneId>> {
+    let p: FindByTxHashParam = ctx.parameter_cursor().get()?;
+    Ok(host.state().tx_hash_index.get(&p.plt_tx_hash).map(|id| *id))
+}
+
+/// Input for `viewByWorkHash`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ByWorkHashParam {
+    pub work_hash: Hash32,
+}
+
+/// Returns the lowest milestone id whose stored `work_hash` matches, if any.
+#[receive(
+    contract = "paylog",
+    name = "viewByWorkHash",
+    parameter = "ByWorkHashParam",
+    return_value = "Option<MilestoneId>"
+)]
+fn view_by_work_hash(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Option<MilestoneId>> {
+    let p: ByWorkHashParam = ctx.parameter_cursor().get()?;
+    let st = host.state();
+    Ok((0..st.milestone_count).find(|id| {
+        st.milestones
+            .get(id)
+            .is_some_and(|m| m.work_hash == Some(p.work_hash))
+    }))
+}
+
+/// Returns every artifact hash approved for a milestone (`None` if out of range).
+#[receive(
+    contract = "paylog",
+    name = "viewWorkHashes",
+    parameter = "ViewParam",
+    return_value = "Option<Vec<Hash32>>"
+)]
+fn view_work_hashes(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Option<Vec<Hash32>>> {
+    let p: ViewParam = ctx.parameter_cursor().get()?;
+    Ok(host
+        .state()
+        .milestones
+        .get(&p.milestone_id)
+        .map(|m| m.work_hashes.clone()))
+}
+
+/// Returns `(id, tx_hash)` for every payment of every released milestone, in
+/// id then payment order (partial payments yield several pairs per id).
+#[receive(
+    contract = "paylog",
+    name = "viewReleasedTxHashes",
+    return_value = "Vec<(MilestoneId, TxHash)>"
+)]
+fn view_released_tx_hashes(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<(MilestoneId, TxHash)>> {
+    let st = host.state();
+    let mut out = Vec::new();
+    for id in 0..st.milestone_count {
+        if let Some(m) = st.milestones.get(&id) {
+            if m.released {
+                out.extend(m.plt_tx_hashes.iter().map(|h| (id, *h)));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// What happened at a `TimelineEntry`.
+#[derive(Serial, Deserial, SchemaType, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineKind {
+    Requested, // release requested (oracle quorum reached)
+    Attested,  // payment confirmed in full
+}
+
+/// One row of `viewTimeline`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct TimelineEntry {
+    pub milestone_id: MilestoneId,
+    pub kind: TimelineKind,
+    pub at_ms: Timestamp,
+}
+
+/// Returns request/attest timestamps of all milestones, ascending by `at_ms`
+/// (ties keep id order, with a milestone's request before its attestation).
+#[receive(
+    contract = "paylog",
+    name = "viewTimeline",
+    return_value = "Vec<TimelineEntry>"
+)]
+fn view_timeline(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Vec<TimelineEntry>> {
+    let st = host.state();
+    let mut out = Vec::new();
+    for id in 0..st.milestone_count {
+        let Some(m) = st.milestones.get(&id) else {
+            continue;
+        };
+        let stamps = [
+            (TimelineKind::Requested, m.requested_at_ms),
+            (TimelineKind::Attested, m.attested_at_ms),
+        ];
+        for (kind, at) in stamps {
+            if let Some(at_ms) = at {
+                out.push(TimelineEntry {
+                    milestone_id: id,
+                    kind,
+                    at_ms,
+                });
+            }
+        }
+    }
+    out.sort_by_key(|e| e.at_ms);
+    Ok(out)
+}
+
+/// Input for `viewStaleRequests`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct StaleRequestsParam {
+    pub older_than_ms: Timestamp, // cut-off: requests strictly before this time
+}
+
+/// Returns ids of requested-but-unpaid milestones requested before `older_than_ms`.
+#[receive(
+    contract = "paylog",
+    name = "viewStaleRequests",
+    parameter = "StaleRequestsParam",
+    return_value = "Vec<MilestoneId>"
+)]
+fn view_stale_requests(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<MilestoneId>> {
+    let p: StaleRequestsParam = ctx.parameter_cursor().get()?;
+    let st = host.state();
+    Ok((0..st.milestone_count)
+        .filter(|id| {
+            st.milestones.get(id).is_some_and(|m| {
+                m.status() == Some(MilestoneStatus::Requested)
+                    && m.requested_at_ms.is_some_and(|t| t < p.older_than_ms)
+            })
+        })
+        .collect())
+}
+
+/// Return model for `viewMilestoneHex`: like `MilestoneView`, but hashes are
+/// lowercase hex strings (64 chars) for JSON consumers.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct MilestoneHexView {
     pub amount_minor: u128,
     pub requested: bool,
     pub released: bool,
-    pub work_hash: Option<Hash32>,
-    pub plt_tx_hash: Option<TxHash>,
+    pub work_hash: Option<String>,
+    pub plt_tx_hashes: Vec<String>,
     pub requested_at_ms: Option<Timestamp>,
     pub attested_at_ms: Option<Timestamp>,
 }
 
-/// Returns the milestone state (or `None` if out of range).
+/// Lowercase hex encoding of a 32-byte hash.
+fn to_hex(bytes: &[u8; 32]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(64);
+    for b in bytes {
+        out.push(DIGITS[(b >> 4) as usize] as char);
+        out.push(DIGITS[(b & 0x0f) as usize] as char);
+    }
+    out
+}
+
+/// Returns the milestone with hex-encoded hashes (or `None` if out of range).
 #[receive(
     contract = "paylog",
-    name = "viewMilestone",
+    name = "viewMilestoneHex",
     parameter = "ViewParam",
-    return_value = "Option<MilestoneView>"
+    return_value = "Option<MilestoneHexView>"
 )]
-fn view_milestone(
+fn view_milestone_hex(
     ctx: &ReceiveContext,
     host: &Host<State>,
-) -> ReceiveResult<Option<MilestoneView>> {
+) -> ReceiveResult<Option<MilestoneHexView>> {
     let p: ViewParam = ctx.parameter_cursor().get()?;
-    let maybe = host.state().milestones.get(p.milestone_id as usize);
-    Ok(maybe.map(|m| MilestoneView {
+    let maybe = host.state().milestones.get(&p.milestone_id);
+    Ok(maybe.map(|m| MilestoneHexView {
         amount_minor: m.amount_minor,
         requested: m.requested,
         released: m.released,
-        work_hash: m.work_hash,
-        plt_tx_hash: m.plt_tx_hash,
+        work_hash: m.work_hash.as_ref().map(to_hex),
+        plt_tx_hashes: m.plt_tx_hashes.iter().map(to_hex).collect(),
         requested_at_ms: m.requested_at_ms,
         attested_at_ms: m.attested_at_ms,
     }))
 }
+
+/// Returns the number of milestones (valid ids are `0..count`).
+#[receive(contract = "paylog", name = "getMilestoneCount", return_value = "u32")]
+fn get_milestone_count(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<u32> {
+    Ok(host.state().milestone_count)
+}
+
+/// Return model for `viewVersion`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct VersionInfo {
+    pub crate_version: String, // Cargo package version of the deployed build
+    pub logic_version: u16,    // LOGIC_VERSION of this module
+}
+
+/// Returns which build this module is (for frontend feature checks).
+#[receive(
+    contract = "paylog",
+    name = "viewVersion",
+    return_value = "VersionInfo"
+)]
+fn view_version(_ctx: &ReceiveContext, _host: &Host<State>) -> ReceiveResult<VersionInfo> {
+    Ok(VersionInfo {
+        crate_version: env!("CARGO_PKG_VERSION").into(),
+        logic_version: LOGIC_VERSION,
+    })
+}
+
+/// Debug helper: echoes the caller as seen by the contract (`Account` or
+/// `Contract`), for diagnosing `Unauthorized` during wallet integration.
+#[receive(contract = "paylog", name = "whoAmI", return_value = "Address")]
+fn who_am_i(ctx: &ReceiveContext, _host: &Host<State>) -> ReceiveResult<Address> {
+    Ok(ctx.sender())
+}
+
+/// Role of the caller in this project (roles are disjoint, see init).
+#[derive(Serial, Deserial, SchemaType, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CallerRole {
+    Client,
+    Freelancer,
+    Oracle,
+    Admin,
+    None, // contracts and unrelated accounts
+}
+
+/// Returns the caller's role, so UIs can disable actions it can't perform.
+#[receive(contract = "paylog", name = "viewMyRole", return_value = "CallerRole")]
+fn view_my_role(ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<CallerRole> {
+    let st = host.state();
+    let role = match ctx.sender() {
+        Address::Account(a) if a == st.client => CallerRole::Client,
+        Address::Account(a) if a == st.freelancer => CallerRole::Freelancer,
+        Address::Account(a) if st.is_oracle(&a) => CallerRole::Oracle,
+        Address::Account(a) if st.is_admin(&a) => CallerRole::Admin,
+        _ => CallerRole::None,
+    };
+    Ok(role)
+}
+
+/// Return model for `viewState`: a plain mirror of `State` with the milestone
+/// map flattened into id order (`State` itself holds `StateMap`s, which have
+/// no schema). `tx_hash_index` is omitted; apart from hashes kept reserved by
+/// `resetMilestone`, it is derivable from the milestones. `idempotency_keys` is
+/// omitted too; each milestone lists its own.
+/// `oracle_bonds` is flattened like the milestones; `bond_tx_index` is omitted.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct StateView {
+    pub project_id: String,
+    pub client: AccountAddress,
+    pub freelancer: AccountAddress,
+    pub oracles: Vec<AccountAddress>,
+    pub threshold: u32,
+    pub plt_decimals: u8,
+    pub milestone_count: u32,
+    pub paused: bool,
+    pub require_acknowledge: bool,
+    pub amount_tolerance_minor: u128,
+    pub sequential: bool,
+    pub confirm_window_ms: Option<u64>,
+    pub closed: bool,
+    pub currency_symbol: Option<String>,
+    pub admin: Option<AccountAddress>,
+    pub request_count: u64,
+    pub confirm_count: u64,
+    pub logic_version: u16,
+    pub strict_ordering: bool,
+    pub require_oracle_cosign: bool,
+    pub oracle_bonds: Vec<(AccountAddress, TxHash)>,
+    pub oracle_active: bool,
+    pub event_seq: u64,
+    pub milestones: Vec<Milestone>,
+}
+
+/// Returns the entire contract state (for debugging and off-chain mirroring).
+#[receive(contract = "paylog", name = "viewState", return_value = "StateView")]
+fn view_state(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<StateView> {
+    let st = host.state();
+    Ok(StateView {
+        project_id: st.project_id.clone(),
+        client: st.client,
+        freelancer: st.freelancer,
+        oracles: st.oracles.clone(),
+        threshold: st.threshold,
+        plt_decimals: st.plt_decimals,
+        milestone_count: st.milestone_count,
+        paused: st.paused,
+        require_acknowledge: st.require_acknowledge,
+        amount_tolerance_minor: st.amount_tolerance_minor,
+        sequential: st.sequential,
+        confirm_window_ms: st.confirm_window_ms,
+        closed: st.closed,
+        currency_symbol: st.currency_symbol.clone(),
+        admin: st.admin,
+        request_count: st.request_count,
+        confirm_count: st.confirm_count,
+        logic_version: st.logic_version,
+        strict_ordering: st.strict_ordering,
+        require_oracle_cosign: st.require_oracle_cosign,
+        oracle_bonds: st.bonds(),
+        oracle_active: st.oracle_active,
+        event_seq: st.event_seq,
+        milestones: (0..st.milestone_count)
+            .filter_map(|id| st.milestones.get(&id).map(|m| m.clone()))
+            .collect(),
+    })
+}
+
+/// Returns the freelancer's requested-but-unpaid earnings in minor units: the
+/// sum over requested, unreleased milestones of `amount_minor` minus any partial
+/// payments already confirmed. The contract has a single freelancer, so this is
+/// unambiguously "their" pending total.
+#[receive(
+    contract = "paylog",
+    name = "viewPendingForFreelancer",
+    return_value = "u128",
+    error = "ContractError"
+)]
+fn view_pending_for_freelancer(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> Result<u128, ContractError> {
+    let mut pending: u128 = 0;
+    for (_, m) in host.state().milestones.iter() {
+        if m.status() == Some(MilestoneStatus::Requested) {
+            pending = add_amount(pending, m.amount_minor.saturating_sub(m.paid_so_far_minor))?;
+        }
+    }
+    Ok(pending)
+}
+
+/// What the client still owes: `amount_minor` over milestones neither released
+/// nor cancelled, net of partial payments already confirmed on them.
+#[receive(
+    contract = "paylog",
+    name = "viewOutstandingAmount",
+    return_value = "u128",
+    error = "ContractError"
+)]
+fn view_outstanding_amount(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> Result<u128, ContractError> {
+    let mut outstanding: u128 = 0;
+    for (_, m) in host.state().milestones.iter() {
+        if !m.released && !m.cancelled {
+            outstanding = add_amount(
+                outstanding,
+                m.amount_minor.saturating_sub(m.paid_so_far_minor),
+            )?;
+        }
+    }
+    Ok(outstanding)
+}
+
+/// Input for `viewReleasedBetween`.
+#[derive(Serial, Deserial, SchemaType, Clone)]
+pub struct ReleasedBetweenParam {
+    pub from_ms: Timestamp, // inclusive
+    pub to_ms: Timestamp,   // inclusive
+}
+
+/// Sum of `amount_minor` over released milestones attested within
+/// `[from_ms, to_ms]` (e.g., for tax reporting). An inverted window
+/// (`from_ms > to_ms`) is simply empty and returns 0 rather than an error.
+#[receive(
+    contract = "paylog",
+    name = "viewReleasedBetween",
+    parameter = "ReleasedBetweenParam",
+    return_value = "u128",
+    error = "ContractError"
+)]
+fn view_released_between(ctx: &ReceiveContext, host: &Host<State>) -> Result<u128, ContractError> {
+    let p: ReleasedBetweenParam = ctx.parameter_cursor().get()?;
+    let mut total: u128 = 0;
+    for (_, m) in host.state().milestones.iter() {
+        let in_window = m
+            .attested_at_ms
+            .is_some_and(|t| p.from_ms <= t && t <= p.to_ms);
+        if m.released && in_window {
+            total = add_amount(total, m.amount_minor)?;
+        }
+    }
+    Ok(total)
+}
+
+// ---- Unit tests --------------------------------------------------------------
+//
+// Pure helpers only; entrypoints are exercised on a simulated chain in
+// `tests/paylog.rs`.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_amount_overflows_near_max() {
+        assert_eq!(add_amount(u128::MAX - 1, 1), Ok(u128::MAX));
+        assert_eq!(add_amount(u128::MAX, 0), Ok(u128::MAX));
+        assert_eq!(add_amount(u128::MAX, 1), Err(ContractError::Overflow));
+        assert_eq!(
+            add_amount(u128::MAX / 2 + 1, u128::MAX / 2 + 1),
+            Err(ContractError::Overflow)
+        );
+    }
+
+    #[test]
+    fn percent_floor_is_exact_without_overflow() {
+        assert_eq!(percent_floor(0, 0), 0);
+        assert_eq!(percent_floor(0, 7), 0);
+        assert_eq!(percent_floor(1, 3), 33);
+        assert_eq!(percent_floor(2, 3), 66);
+        assert_eq!(percent_floor(999, 1000), 99);
+        assert_eq!(percent_floor(u128::MAX - 1, u128::MAX), 99);
+        assert_eq!(percent_floor(u128::MAX / 2, u128::MAX), 49);
+        assert_eq!(percent_floor(u128::MAX, u128::MAX), 100);
+    }
+
+    #[test]
+    fn require_role_matches_exact_account() {
+        let client = AccountAddress([1u8; 32]);
+        let other = AccountAddress([2u8; 32]);
+        assert_eq!(require_role(client, client), Ok(()));
+        assert_eq!(
+            require_role(other, client),
+            Err(ContractError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn is_printable_rejects_control_characters() {
+        assert!(is_printable("wlog-local"));
+        assert!(is_printable("café-プロジェクト-🚀"));
+        for s in ["a\nb", "a\tb", "a\0b", "a\u{7f}b", "a\u{85}b"] {
+            assert!(!is_printable(s), "{s:?}");
+        }
+    }
+}
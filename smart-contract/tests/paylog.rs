@@ -0,0 +1,1258 @@
+//! Integration tests for the `paylog` contract on a simulated chain.
+//!
+//! The module is compiled once per test run with
+//! `cargo build --release --target wasm32-unknown-unknown` into
+//! `target/wasm-test` (a separate target dir, so the outer `cargo test` lock
+//! is not contended), then deployed into a fresh `Chain` for every test.
+//!
+//! With `PAYLOG_NO_STD=1` the module is built without the `std` feature (with
+//! `bump_alloc`) into `target/wasm-test-no-std` instead, so the same tests run
+//! against the `no_std` build. `concordium-std` needs nightly for that (with
+//! the `rust-src` component and the `wasm32-unknown-unknown` target):
+//! `PAYLOG_NO_STD=1 cargo +nightly test --test paylog`.
+
+use concordium_smart_contract_testing::*;
+use concordium_std::{Deserial, PublicKeyEd25519, Serial, SignatureEd25519};
+use ed25519_dalek::{Signer as _, SigningKey};
+use smart_contract::{
+    AmendAmountParam, AttestedEvent, CancelParam, CommitWorkParam, ConfirmParam, ContractError,
+    CosignParam, FindByTxHashParam, InitParams, MilestoneView, OracleSignature, ReasonCode,
+    ReopenParam, RequestParam, ResetParam, StateView, UpdateWorkHashParam, ViewParam,
+};
+use std::{env, path::PathBuf, process::Command, sync::OnceLock};
+
+// ---- Accounts ----------------------------------------------------------------
+
+const CLIENT: AccountAddress = AccountAddress([1u8; 32]);
+const FREELANCER: AccountAddress = AccountAddress([2u8; 32]);
+const ORACLE: AccountAddress = AccountAddress([3u8; 32]);
+const ORACLE_B: AccountAddress = AccountAddress([4u8; 32]);
+
+/// Per-call energy budget; well above anything a single call here needs.
+const ENERGY: Energy = Energy { energy: 3_000_000 };
+
+/// Block time every test starts at.
+const START_MS: u64 = 1_000_000;
+
+const WORK_HASH: [u8; 32] = [7u8; 32];
+
+// ---- Harness -----------------------------------------------------------------
+
+/// Path of the compiled module, built on first use.
+fn module_path() -> &'static PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let no_std = env::var_os("PAYLOG_NO_STD").is_some();
+        let target_dir = manifest_dir.join("target").join(if no_std {
+            "wasm-test-no-std"
+        } else {
+            "wasm-test"
+        });
+        // Nightly's prebuilt wasm std uses post-MVP opcodes, so it is rebuilt
+        // from source with the flags below.
+        let features: &[&str] = if no_std {
+            &[
+                "--no-default-features",
+                "--features",
+                "bump_alloc",
+                "-Zbuild-std=std,panic_abort",
+            ]
+        } else {
+            &[]
+        };
+        // `target-cpu=mvp` and compressed relocations keep the output within
+        // the Wasm subset the chain accepts.
+        let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+            .current_dir(&manifest_dir)
+            .args([
+                "build",
+                "--release",
+                "--lib",
+                "--target",
+                "wasm32-unknown-unknown",
+            ])
+            .args(features)
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .env(
+                "RUSTFLAGS",
+                "-C target-cpu=mvp -C link-arg=--compress-relocations",
+            )
+            .env_remove("CARGO_ENCODED_RUSTFLAGS")
+            .env_remove("RUSTC_WRAPPER")
+            .env_remove("RUSTC_WORKSPACE_WRAPPER")
+            .status()
+            .expect("run cargo build for wasm32-unknown-unknown");
+        assert!(status.success(), "building the contract module failed");
+        target_dir.join("wasm32-unknown-unknown/release/smart_contract.wasm")
+    })
+}
+
+/// A chain with the module deployed and the role accounts funded.
+struct TestEnv {
+    chain: Chain,
+    module: ModuleReference,
+}
+
+impl TestEnv {
+    fn new() -> Self {
+        let mut chain = Chain::builder()
+            .block_time(Timestamp::from_timestamp_millis(START_MS))
+            .build()
+            .expect("build chain");
+        for account in [CLIENT, FREELANCER, ORACLE_B] {
+            chain.create_account(Account::new(account, Amount::from_ccd(10_000)));
+        }
+        // ORACLE holds a real key so relayed approvals can be verified.
+        let balance = Amount::from_ccd(10_000);
+        chain.create_account(Account::new_with_keys(
+            ORACLE,
+            AccountBalance::new(balance, Amount::zero(), Amount::zero()).unwrap(),
+            AccountAccessStructure::singleton(oracle_key().verifying_key()),
+        ));
+        let module = module_load_v1_raw(module_path()).expect("load module");
+        let module = chain
+            .module_deploy_v1(Signer::with_one_key(), CLIENT, module)
+            .expect("deploy module")
+            .module_reference;
+        TestEnv { chain, module }
+    }
+
+    fn init(&mut self, p: &InitParams) -> Result<ContractAddress, ContractError> {
+        let payload = InitContractPayload {
+            amount: Amount::zero(),
+            mod_ref: self.module,
+            init_name: OwnedContractName::new_unchecked("init_paylog".into()),
+            param: OwnedParameter::from_serial(p).expect("init parameter"),
+        };
+        match self
+            .chain
+            .contract_init(Signer::with_one_key(), CLIENT, ENERGY, payload)
+        {
+            Ok(success) => Ok(success.contract_address),
+            Err(ContractInitError {
+                kind:
+                    ContractInitErrorKind::ExecutionError {
+                        error: InitExecutionError::Reject { reason, .. },
+                        ..
+                    },
+                ..
+            }) => Err(error_from_reject_code(reason)),
+            Err(e) => panic!("init failed without a reject: {e:?}"),
+        }
+    }
+
+    /// Sends `entrypoint(param)` from `sender` and returns the energy used.
+    fn update<P: Serial>(
+        &mut self,
+        contract: ContractAddress,
+        sender: AccountAddress,
+        entrypoint: &str,
+        param: &P,
+    ) -> Result<Energy, ContractError> {
+        self.update_as(
+            contract,
+            sender,
+            Address::Account(sender),
+            entrypoint,
+            param,
+        )
+    }
+
+    /// Like `update`, but with an arbitrary `sender` (e.g. a contract) on a
+    /// transaction signed by `invoker`.
+    fn update_as<P: Serial>(
+        &mut self,
+        contract: ContractAddress,
+        invoker: AccountAddress,
+        sender: Address,
+        entrypoint: &str,
+        param: &P,
+    ) -> Result<Energy, ContractError> {
+        self.send(contract, invoker, sender, entrypoint, param)
+            .map(|success| success.energy_used)
+    }
+
+    /// Like `update`, but returns the last event the call logged.
+    fn update_logged<P: Serial, E: Deserial>(
+        &mut self,
+        contract: ContractAddress,
+        sender: AccountAddress,
+        entrypoint: &str,
+        param: &P,
+    ) -> Result<E, ContractError> {
+        let success = self.send(
+            contract,
+            sender,
+            Address::Account(sender),
+            entrypoint,
+            param,
+        )?;
+        let (_, events) = success.events().last().expect("call logged events");
+        Ok(events
+            .last()
+            .expect("call logged events")
+            .parse()
+            .expect("event type"))
+    }
+
+    fn send<P: Serial>(
+        &mut self,
+        contract: ContractAddress,
+        invoker: AccountAddress,
+        sender: Address,
+        entrypoint: &str,
+        param: &P,
+    ) -> Result<ContractInvokeSuccess, ContractError> {
+        let payload = UpdateContractPayload {
+            amount: Amount::zero(),
+            address: contract,
+            receive_name: OwnedReceiveName::new_unchecked(format!("paylog.{entrypoint}")),
+            message: OwnedParameter::from_serial(param).expect("update parameter"),
+        };
+        self.chain
+            .contract_update(Signer::with_one_key(), invoker, sender, ENERGY, payload)
+            .map_err(|e| e.parse_return_value().expect("ContractError return value"))
+    }
+
+    /// Invokes a view entrypoint without committing anything.
+    fn view<P: Serial, R: Deserial>(
+        &mut self,
+        contract: ContractAddress,
+        entrypoint: &str,
+        param: &P,
+    ) -> R {
+        let payload = UpdateContractPayload {
+            amount: Amount::zero(),
+            address: contract,
+            receive_name: OwnedReceiveName::new_unchecked(format!("paylog.{entrypoint}")),
+            message: OwnedParameter::from_serial(param).expect("view parameter"),
+        };
+        self.chain
+            .contract_invoke(CLIENT, Address::Account(CLIENT), ENERGY, payload)
+            .expect("view call")
+            .parse_return_value()
+            .expect("view return value")
+    }
+
+    fn milestone(&mut self, contract: ContractAddress, milestone_id: u32) -> MilestoneView {
+        self.view::<_, Option<MilestoneView>>(
+            contract,
+            "viewMilestone",
+            &ViewParam { milestone_id },
+        )
+        .expect("milestone exists")
+    }
+}
+
+/// Init carries no return value on reject, only the `Reject` derive's code:
+/// variant `i` of `ContractError` rejects with `-(i + 1)`.
+fn error_from_reject_code(reason: i32) -> ContractError {
+    let variant = u8::try_from(-reason - 1).expect("ContractError reject code");
+    from_bytes(&[variant]).expect("ContractError variant")
+}
+
+/// The key of ORACLE's account.
+fn oracle_key() -> SigningKey {
+    SigningKey::from_bytes(&[42u8; 32])
+}
+
+// ---- Parameter builders --------------------------------------------------------
+
+/// Minimal valid project: one oracle, threshold 1, no optional features.
+fn init_params(amounts: Vec<u128>) -> InitParams {
+    InitParams {
+        project_id: "wlog-test".into(),
+        client: CLIENT,
+        freelancer: FREELANCER,
+        oracles: vec![ORACLE],
+        threshold: 1,
+        amounts,
+        plt_decimals: 6,
+        deadlines_ms: None,
+        require_acknowledge: false,
+        amount_tolerance_minor: None,
+        sequential: false,
+        confirm_window_ms: None,
+        ref_ids: None,
+        labels: None,
+        currency_symbol: None,
+        admin: None,
+        oracle_per_milestone: None,
+        strict_ordering: true,
+        depends_on: None,
+        require_oracle_cosign: false,
+        decimals_per_milestone: None,
+    }
+}
+
+/// Oracle approval of `milestone_id` with `WORK_HASH`.
+fn request(milestone_id: u32) -> RequestParam {
+    RequestParam {
+        milestone_id,
+        work_hash: WORK_HASH,
+        salt: None,
+        work_hashes: None,
+        signed: None,
+    }
+}
+
+/// Client confirmation of `paid` minor units, paid by PLT tx `[tx; 32]`.
+fn confirm(milestone_id: u32, paid: u128, tx: u8) -> ConfirmParam {
+    ConfirmParam {
+        milestone_id,
+        paid_amount_minor: paid,
+        plt_tx_hash: [tx; 32],
+        token_id: "PAYLOGPLT".into(),
+        memo: None,
+        idempotency_key: None,
+        payer: CLIENT,
+        payee: FREELANCER,
+        work_hash: None,
+        salt: None,
+    }
+}
+
+// ---- Init ----------------------------------------------------------------------
+
+#[test]
+fn init_accepts_distinct_roles() {
+    let mut env = TestEnv::new();
+    assert!(env.init(&init_params(vec![100])).is_ok());
+}
+
+#[test]
+fn init_rejects_client_as_freelancer() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.freelancer = CLIENT;
+    assert_eq!(env.init(&p), Err(ContractError::InvalidRole));
+}
+
+#[test]
+fn init_rejects_client_as_oracle() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.oracles = vec![CLIENT];
+    assert_eq!(env.init(&p), Err(ContractError::InvalidRole));
+}
+
+#[test]
+fn init_rejects_freelancer_as_oracle() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.oracles = vec![FREELANCER];
+    assert_eq!(env.init(&p), Err(ContractError::InvalidRole));
+}
+
+#[test]
+fn init_rejects_total_above_u128_max() {
+    let mut env = TestEnv::new();
+    let p = init_params(vec![u128::MAX, 1]);
+    assert_eq!(env.init(&p), Err(ContractError::Overflow));
+}
+
+#[test]
+fn init_rejects_blank_project_id() {
+    for project_id in ["", "   ", " \t\n "] {
+        let mut env = TestEnv::new();
+        let mut p = init_params(vec![100]);
+        p.project_id = project_id.into();
+        assert_eq!(env.init(&p), Err(ContractError::EmptyProjectId));
+    }
+}
+
+#[test]
+fn init_bounds_project_id_length() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.project_id = "a".repeat(128);
+    assert!(env.init(&p).is_ok());
+    p.project_id = "a".repeat(129);
+    assert_eq!(env.init(&p), Err(ContractError::ProjectIdTooLong));
+}
+
+#[test]
+fn init_rejects_control_characters_in_project_id() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.project_id = "wlog\u{1b}[2J".into();
+    assert_eq!(env.init(&p), Err(ContractError::InvalidProjectId));
+    p.project_id = "wlog-ünïcødé-日本".into();
+    assert!(env.init(&p).is_ok());
+}
+
+#[test]
+fn init_bounds_oracle_count() {
+    // Eight oracles and a max-length project id still fit in `InitEvent`.
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.project_id = "p".repeat(128);
+    p.oracles = (0..8).map(|i| AccountAddress([10 + i; 32])).collect();
+    assert!(env.init(&p).is_ok());
+    p.oracles.push(AccountAddress([20u8; 32]));
+    assert_eq!(env.init(&p), Err(ContractError::TooManyOracles));
+}
+
+// ---- requestRelease ------------------------------------------------------------
+
+#[test]
+fn request_rejects_zero_work_hash() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    let mut p = request(0);
+    p.work_hash = [0u8; 32];
+    assert_eq!(
+        env.update(contract, ORACLE, "requestRelease", &p),
+        Err(ContractError::InvalidWorkHash)
+    );
+    let mut p = request(0);
+    p.work_hashes = Some(vec![[8u8; 32], [0u8; 32]]);
+    assert_eq!(
+        env.update(contract, ORACLE, "requestRelease", &p),
+        Err(ContractError::InvalidWorkHash)
+    );
+
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    assert_eq!(env.milestone(contract, 0).work_hash, Some(WORK_HASH));
+}
+
+// ---- Relayed oracle approvals -------------------------------------------------
+
+/// `p` with an approval for `oracle` signed by `key`, over the message
+/// `resolve_approver` checks (contract, id, nonce, work hash, extra hashes).
+fn signed(
+    contract: ContractAddress,
+    mut p: RequestParam,
+    request_nonce: u32,
+    oracle: AccountAddress,
+    key: &SigningKey,
+) -> RequestParam {
+    let extras = p.work_hashes.clone().unwrap_or_default();
+    let message = to_bytes(&(contract, p.milestone_id, request_nonce, p.work_hash, extras));
+    p.signed = Some(OracleSignature {
+        oracle,
+        oracle_pubkey: PublicKeyEd25519(key.verifying_key().to_bytes()),
+        signature: SignatureEd25519(key.sign(&message).to_bytes()),
+    });
+    p
+}
+
+#[test]
+fn relayed_approval_from_any_account() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    let p = signed(contract, request(0), 0, ORACLE, &oracle_key());
+    env.update(contract, FREELANCER, "requestRelease", &p)
+        .unwrap();
+    let ms = env.milestone(contract, 0);
+    assert!(ms.requested);
+    assert_eq!(ms.work_hash, Some(WORK_HASH));
+}
+
+#[test]
+fn relayed_approval_rejects_foreign_key() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    // A valid signature, but by a key that is not on ORACLE's account.
+    let p = signed(
+        contract,
+        request(0),
+        0,
+        ORACLE,
+        &SigningKey::from_bytes(&[43u8; 32]),
+    );
+    assert_eq!(
+        env.update(contract, FREELANCER, "requestRelease", &p),
+        Err(ContractError::InvalidSignature)
+    );
+}
+
+#[test]
+fn relayed_approval_binds_artifact_set() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    // Signed without extra hashes, submitted with one.
+    let mut p = signed(contract, request(0), 0, ORACLE, &oracle_key());
+    p.work_hashes = Some(vec![[8u8; 32]]);
+    assert_eq!(
+        env.update(contract, FREELANCER, "requestRelease", &p),
+        Err(ContractError::InvalidSignature)
+    );
+    assert!(!env.milestone(contract, 0).requested);
+}
+
+#[test]
+fn relayed_approval_cannot_be_replayed_after_reopen() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    let p = signed(contract, request(0), 0, ORACLE, &oracle_key());
+    env.update(contract, FREELANCER, "requestRelease", &p)
+        .unwrap();
+    env.update(
+        contract,
+        ORACLE,
+        "reopenMilestone",
+        &ReopenParam { milestone_id: 0 },
+    )
+    .unwrap();
+    assert_eq!(env.milestone(contract, 0).request_nonce, 1);
+
+    assert_eq!(
+        env.update(contract, FREELANCER, "requestRelease", &p),
+        Err(ContractError::InvalidSignature)
+    );
+    // A fresh signature over the new nonce is accepted.
+    let p = signed(contract, request(0), 1, ORACLE, &oracle_key());
+    env.update(contract, FREELANCER, "requestRelease", &p)
+        .unwrap();
+}
+
+// ---- Sequential ordering -------------------------------------------------------
+
+#[test]
+fn sequential_releases_in_id_order() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100, 100]);
+    p.sequential = true;
+    let contract = env.init(&p).unwrap();
+
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(1))
+        .unwrap();
+    env.update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 2))
+        .unwrap();
+    assert!(env.milestone(contract, 1).released);
+}
+
+#[test]
+fn sequential_rejects_out_of_order_request() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100, 100]);
+    p.sequential = true;
+    let contract = env.init(&p).unwrap();
+
+    assert_eq!(
+        env.update(contract, ORACLE, "requestRelease", &request(1)),
+        Err(ContractError::PreviousNotReleased)
+    );
+    // Requested but unpaid is not enough either.
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    assert_eq!(
+        env.update(contract, ORACLE, "requestRelease", &request(1)),
+        Err(ContractError::PreviousNotReleased)
+    );
+}
+
+#[test]
+fn sequential_treats_cancelled_predecessor_as_settled() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100, 100]);
+    p.sequential = true;
+    let contract = env.init(&p).unwrap();
+
+    let cancel = CancelParam {
+        milestone_id: 0,
+        reason: ReasonCode::ScopeChange,
+        reason_hash: [9u8; 32],
+    };
+    env.update(contract, CLIENT, "cancelMilestone", &cancel)
+        .unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(1))
+        .unwrap();
+    env.update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 1))
+        .unwrap();
+    assert!(env.milestone(contract, 1).released);
+}
+
+// ---- confirmPayment ------------------------------------------------------------
+
+#[test]
+fn confirm_rejects_duplicate_tx_hash() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100, 100])).unwrap();
+    for id in [0, 1] {
+        env.update(contract, ORACLE, "requestRelease", &request(id))
+            .unwrap();
+    }
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+
+    // The same transfer cannot be counted for another milestone ...
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 1)),
+        Err(ContractError::DuplicateTxHash)
+    );
+    // ... while a distinct one goes through.
+    assert!(env
+        .update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 2))
+        .is_ok());
+}
+
+#[test]
+fn confirm_finalizes_exactly_at_tolerance() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100, 100]);
+    p.amount_tolerance_minor = Some(5);
+    let contract = env.init(&p).unwrap();
+    for id in [0, 1] {
+        env.update(contract, ORACLE, "requestRelease", &request(id))
+            .unwrap();
+    }
+
+    // Underpaying by exactly the tolerance finalizes.
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 95, 1))
+        .unwrap();
+    assert!(env.milestone(contract, 0).released);
+
+    // Overpaying by tolerance + 1 is rejected; by exactly the tolerance it finalizes.
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(1, 106, 2)),
+        Err(ContractError::AmountMismatch)
+    );
+    env.update(contract, CLIENT, "confirmPayment", &confirm(1, 105, 2))
+        .unwrap();
+    assert!(env.milestone(contract, 1).released);
+}
+
+#[test]
+fn confirm_below_tolerance_stays_partial() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.amount_tolerance_minor = Some(5);
+    let contract = env.init(&p).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+
+    // Underpaying by tolerance + 1 is only a partial payment.
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 94, 1))
+        .unwrap();
+    let ms = env.milestone(contract, 0);
+    assert!(!ms.released);
+    assert_eq!(ms.paid_so_far_minor, 94);
+}
+
+#[test]
+fn confirm_logs_memo_in_attested_event() {
+    // Two milestones, so `AttestedEvent` is not followed by `ProjectSettledEvent`.
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100, 100])).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+
+    let memo = r#"{"p":"wlog","m":0,"h":"0707"} ✓"#;
+    let mut p = confirm(0, 100, 1);
+    p.memo = Some(memo.into());
+    let ev: AttestedEvent = env
+        .update_logged(contract, CLIENT, "confirmPayment", &p)
+        .unwrap();
+    assert_eq!(ev.memo.as_deref(), Some(memo));
+    assert_eq!(ev.token_id, "PAYLOGPLT");
+    assert_eq!(env.milestone(contract, 0).memo.as_deref(), Some(memo));
+}
+
+#[test]
+fn confirm_rejects_zero_tx_hash() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 0)),
+        Err(ContractError::InvalidTxHash)
+    );
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+    assert_eq!(env.milestone(contract, 0).plt_tx_hashes, vec![[1u8; 32]]);
+}
+
+#[test]
+fn confirm_window_is_inclusive() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100, 100]);
+    p.confirm_window_ms = Some(1_000);
+    let contract = env.init(&p).unwrap();
+    for id in [0, 1] {
+        env.update(contract, ORACLE, "requestRelease", &request(id))
+            .unwrap();
+    }
+
+    // Exactly at the window edge is still accepted ...
+    env.chain
+        .tick_block_time(Duration::from_millis(1_000))
+        .unwrap();
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+
+    // ... one millisecond later the request is void.
+    env.chain.tick_block_time(Duration::from_millis(1)).unwrap();
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 2)),
+        Err(ContractError::ConfirmWindowExpired)
+    );
+}
+
+#[test]
+fn confirm_rejects_zero_payment_within_tolerance() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![5]);
+    p.amount_tolerance_minor = Some(10);
+    let contract = env.init(&p).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+
+    // |0 - 5| <= 10, but a zero payment attests nothing.
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(0, 0, 1)),
+        Err(ContractError::AmountMismatch)
+    );
+    assert!(!env.milestone(contract, 0).released);
+}
+
+// ---- Batches -------------------------------------------------------------------
+
+/// Full-size batches of worst-case items (every extra work hash, max memo,
+/// idempotency keys, the last item settling the project) must stay far inside
+/// the per-transaction energy limit (3,000,000 NRG); see `MAX_BATCH_LEN`.
+#[test]
+fn max_len_batches_fit_energy_limit() {
+    const BATCH_LEN: u32 = 32;
+    let mut env = TestEnv::new();
+    let contract = env
+        .init(&init_params(vec![100; BATCH_LEN as usize]))
+        .unwrap();
+
+    let requests: Vec<_> = (0..BATCH_LEN)
+        .map(|id| {
+            let mut p = request(id);
+            p.work_hashes = Some(vec![[8u8; 32]; 15]);
+            p
+        })
+        .collect();
+    let request_energy = env
+        .update(contract, ORACLE, "requestReleaseBatch", &requests)
+        .unwrap();
+    let confirms: Vec<_> = (0..BATCH_LEN)
+        .map(|id| {
+            let mut p = confirm(id, 100, id as u8 + 1);
+            p.memo = Some("m".repeat(256));
+            p.idempotency_key = Some([id as u8; 16]);
+            p
+        })
+        .collect();
+    let confirm_energy = env
+        .update(contract, CLIENT, "confirmPaymentBatch", &confirms)
+        .unwrap();
+
+    assert!(request_energy.energy < 150_000);
+    assert!(confirm_energy.energy < 300_000);
+    assert!(env.milestone(contract, BATCH_LEN - 1).released);
+}
+
+// ---- Implicit requests (strict_ordering) --------------------------------------
+
+#[test]
+fn strict_ordering_requires_oracle_request() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    let mut p = confirm(0, 100, 1);
+    p.work_hash = Some(WORK_HASH);
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::NotRequested)
+    );
+}
+
+#[test]
+fn non_strict_confirm_requests_implicitly() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.strict_ordering = false;
+    let contract = env.init(&p).unwrap();
+
+    // A work hash is mandatory for the implicit request.
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1)),
+        Err(ContractError::NotRequested)
+    );
+    let mut p = confirm(0, 100, 1);
+    p.work_hash = Some(WORK_HASH);
+    env.update(contract, CLIENT, "confirmPayment", &p).unwrap();
+
+    let ms = env.milestone(contract, 0);
+    assert!(ms.requested && ms.released);
+    assert_eq!(ms.work_hash, Some(WORK_HASH));
+    let state: StateView = env.view(contract, "viewState", &());
+    assert_eq!((state.request_count, state.confirm_count), (1, 1));
+}
+
+#[test]
+fn implicit_request_checks_deadline() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.strict_ordering = false;
+    p.deadlines_ms = Some(vec![Timestamp::from_timestamp_millis(START_MS + 10)]);
+    let contract = env.init(&p).unwrap();
+
+    env.chain
+        .tick_block_time(Duration::from_millis(11))
+        .unwrap();
+    let mut p = confirm(0, 100, 1);
+    p.work_hash = Some(WORK_HASH);
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::DeadlinePassed)
+    );
+}
+
+#[test]
+fn implicit_request_checks_commitment() {
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.strict_ordering = false;
+    let contract = env.init(&p).unwrap();
+    let commit = CommitWorkParam {
+        milestone_id: 0,
+        commitment: [5u8; 32],
+    };
+    env.update(contract, ORACLE, "commitWork", &commit).unwrap();
+
+    // Neither a missing nor a wrong salt opens the commitment.
+    let mut p = confirm(0, 100, 1);
+    p.work_hash = Some(WORK_HASH);
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::CommitmentMismatch)
+    );
+    p.salt = Some([6u8; 32]);
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::CommitmentMismatch)
+    );
+}
+
+// ---- amendAmount ---------------------------------------------------------------
+
+#[test]
+fn amend_rejects_partly_paid_milestone() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 60, 1))
+        .unwrap();
+    env.update(
+        contract,
+        ORACLE,
+        "reopenMilestone",
+        &ReopenParam { milestone_id: 0 },
+    )
+    .unwrap();
+
+    // The 60 already paid survives the reopen; 50 could never be confirmed.
+    let amend = AmendAmountParam {
+        milestone_id: 0,
+        new_amount_minor: 50,
+    };
+    assert_eq!(
+        env.update(contract, CLIENT, "amendAmount", &amend),
+        Err(ContractError::AmountMismatch)
+    );
+    assert_eq!(env.milestone(contract, 0).amount_minor, 100);
+}
+
+#[test]
+fn amend_changes_unpaid_amount() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    let amend = AmendAmountParam {
+        milestone_id: 0,
+        new_amount_minor: 50,
+    };
+    env.update(contract, CLIENT, "amendAmount", &amend).unwrap();
+    assert_eq!(env.milestone(contract, 0).amount_minor, 50);
+}
+
+// ---- oracleCosign --------------------------------------------------------------
+
+/// Two oracles, threshold 1, co-sign required.
+fn cosign_params(amounts: Vec<u128>) -> InitParams {
+    let mut p = init_params(amounts);
+    p.oracles = vec![ORACLE, ORACLE_B];
+    p.require_oracle_cosign = true;
+    p
+}
+
+#[test]
+fn cosign_requires_the_flag() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    assert_eq!(
+        env.update(
+            contract,
+            ORACLE,
+            "oracleCosign",
+            &CosignParam { milestone_id: 0 }
+        ),
+        Err(ContractError::CosignNotRequired)
+    );
+}
+
+#[test]
+fn cosign_releases_in_either_order() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&cosign_params(vec![100, 100])).unwrap();
+    for id in [0, 1] {
+        env.update(contract, ORACLE, "requestRelease", &request(id))
+            .unwrap();
+    }
+
+    // Client first: the milestone waits for the co-sign.
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+    let ms = env.milestone(contract, 0);
+    assert!(ms.client_confirmed && !ms.released);
+    env.update(
+        contract,
+        ORACLE_B,
+        "oracleCosign",
+        &CosignParam { milestone_id: 0 },
+    )
+    .unwrap();
+    assert!(env.milestone(contract, 0).released);
+
+    // Oracle first: the client's confirm releases.
+    env.update(
+        contract,
+        ORACLE_B,
+        "oracleCosign",
+        &CosignParam { milestone_id: 1 },
+    )
+    .unwrap();
+    assert!(!env.milestone(contract, 1).released);
+    env.update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 2))
+        .unwrap();
+    assert!(env.milestone(contract, 1).released);
+}
+
+#[test]
+fn work_hash_update_clears_cosign() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&cosign_params(vec![100])).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    env.update(
+        contract,
+        ORACLE_B,
+        "oracleCosign",
+        &CosignParam { milestone_id: 0 },
+    )
+    .unwrap();
+
+    // B co-signed the old hash only, so a full confirm must wait again.
+    let update = UpdateWorkHashParam {
+        milestone_id: 0,
+        work_hash: [8u8; 32],
+    };
+    env.update(contract, ORACLE, "updateWorkHash", &update)
+        .unwrap();
+    assert!(!env.milestone(contract, 0).oracle_cosigned);
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+    assert!(!env.milestone(contract, 0).released);
+
+    env.update(
+        contract,
+        ORACLE_B,
+        "oracleCosign",
+        &CosignParam { milestone_id: 0 },
+    )
+    .unwrap();
+    let ms = env.milestone(contract, 0);
+    assert!(ms.released);
+    assert_eq!(ms.work_hash, Some([8u8; 32]));
+}
+
+// ---- resetMilestone ------------------------------------------------------------
+
+/// Requests and fully confirms milestone 0 with tx `[1; 32]` and key `[4; 16]`.
+fn release_with_key(env: &mut TestEnv, contract: ContractAddress) {
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    let mut p = confirm(0, 100, 1);
+    p.idempotency_key = Some([4u8; 16]);
+    env.update(contract, CLIENT, "confirmPayment", &p).unwrap();
+}
+
+#[test]
+fn reset_of_unreleased_milestone_is_pristine() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    let mut p = confirm(0, 40, 1);
+    p.idempotency_key = Some([4u8; 16]);
+    env.update(contract, CLIENT, "confirmPayment", &p).unwrap();
+
+    let reset = ResetParam {
+        milestone_id: 0,
+        force: false,
+    };
+    env.update(contract, CLIENT, "resetMilestone", &reset)
+        .unwrap();
+    let ms = env.milestone(contract, 0);
+    assert!(!ms.requested && ms.work_hash.is_none());
+    assert!(ms.plt_tx_hashes.is_empty());
+    assert_eq!((ms.paid_so_far_minor, ms.request_nonce), (0, 1));
+
+    // Neither the old tx hash nor the old key blocks a fresh confirm.
+    release_with_key(&mut env, contract);
+    let ms = env.milestone(contract, 0);
+    assert!(ms.released);
+    assert_eq!(ms.paid_so_far_minor, 100);
+}
+
+#[test]
+fn reset_of_released_milestone_needs_force() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    release_with_key(&mut env, contract);
+
+    let mut reset = ResetParam {
+        milestone_id: 0,
+        force: false,
+    };
+    assert_eq!(
+        env.update(contract, CLIENT, "resetMilestone", &reset),
+        Err(ContractError::AlreadyReleased)
+    );
+    reset.force = true;
+    env.update(contract, CLIENT, "resetMilestone", &reset)
+        .unwrap();
+    assert!(!env.milestone(contract, 0).released);
+
+    // The retried key is no longer a silent no-op: it releases again.
+    release_with_key(&mut env, contract);
+    assert!(env.milestone(contract, 0).released);
+}
+
+#[test]
+fn reset_keeps_tx_hashes_reserved_for_the_milestone() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100, 100])).unwrap();
+    for id in [0, 1] {
+        env.update(contract, ORACLE, "requestRelease", &request(id))
+            .unwrap();
+    }
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 40, 1))
+        .unwrap();
+    let reset = ResetParam {
+        milestone_id: 0,
+        force: false,
+    };
+    env.update(contract, CLIENT, "resetMilestone", &reset)
+        .unwrap();
+
+    // The wiped partial payment cannot be counted on another milestone.
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(1, 100, 1)),
+        Err(ContractError::DuplicateTxHash)
+    );
+    let find = FindByTxHashParam {
+        plt_tx_hash: [1u8; 32],
+    };
+    let found: Option<u32> = env.view(contract, "findByTxHash", &find);
+    assert_eq!(found, Some(0));
+}
+
+// ---- Event size ----------------------------------------------------------------
+
+#[test]
+fn confirm_bounds_attested_event_text() {
+    // Max project id and token id leave 512 - 223 - 256 = 33 bytes of memo.
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.project_id = "p".repeat(128);
+    let contract = env.init(&p).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    let before: StateView = env.view(contract, "viewState", &());
+
+    let mut p = confirm(0, 100, 1);
+    p.token_id = "t".repeat(128);
+    p.memo = Some("m".repeat(34));
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::MemoTooLong)
+    );
+    let after: StateView = env.view(contract, "viewState", &());
+    assert_eq!(to_bytes(&after), to_bytes(&before));
+
+    // Exactly at the budget the event still fits.
+    p.memo = Some("m".repeat(33));
+    env.update(contract, CLIENT, "confirmPayment", &p).unwrap();
+    assert!(env.milestone(contract, 0).released);
+}
+
+#[test]
+fn confirm_rejects_max_memo_with_uuid_project_id() {
+    // 36-byte UUID + 9-byte token id leave 244 bytes of memo, not 256.
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.project_id = "123e4567-e89b-12d3-a456-426614174000".into();
+    let contract = env.init(&p).unwrap();
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+
+    let mut p = confirm(0, 100, 1);
+    p.memo = Some("m".repeat(256));
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::MemoTooLong)
+    );
+    p.memo = Some("m".repeat(244));
+    env.update(contract, CLIENT, "confirmPayment", &p).unwrap();
+    assert!(env.milestone(contract, 0).released);
+}
+
+// ---- Rollback ------------------------------------------------------------------
+
+#[test]
+fn failed_batch_item_reverts_earlier_items() {
+    // A failing call is rolled back as a whole, state and logs alike; this is
+    // what makes mutating before `logger.log` safe.
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100, 100])).unwrap();
+    for id in [0, 1] {
+        env.update(contract, ORACLE, "requestRelease", &request(id))
+            .unwrap();
+    }
+    let before: StateView = env.view(contract, "viewState", &());
+
+    let mut first = confirm(0, 100, 1);
+    first.idempotency_key = Some([4u8; 16]);
+    let batch = vec![first.clone(), confirm(1, 100, 0)];
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPaymentBatch", &batch),
+        Err(ContractError::InvalidTxHash)
+    );
+    let after: StateView = env.view(contract, "viewState", &());
+    assert_eq!(to_bytes(&after), to_bytes(&before));
+
+    // Neither the tx hash nor the idempotency key was persisted.
+    env.update(contract, CLIENT, "confirmPayment", &first)
+        .unwrap();
+    assert!(env.milestone(contract, 0).released);
+}
+
+// ---- Out-of-range ids ----------------------------------------------------------
+
+#[test]
+fn max_milestone_id_is_invalid() {
+    // Sequential mode also looks up `id - 1`, so it is the stricter case.
+    let mut env = TestEnv::new();
+    let mut p = init_params(vec![100]);
+    p.sequential = true;
+    let contract = env.init(&p).unwrap();
+
+    assert_eq!(
+        env.update(contract, ORACLE, "requestRelease", &request(u32::MAX)),
+        Err(ContractError::InvalidMilestone)
+    );
+    assert_eq!(
+        env.update(
+            contract,
+            CLIENT,
+            "confirmPayment",
+            &confirm(u32::MAX, 100, 1)
+        ),
+        Err(ContractError::InvalidMilestone)
+    );
+    let view: Option<MilestoneView> = env.view(
+        contract,
+        "viewMilestone",
+        &ViewParam {
+            milestone_id: u32::MAX,
+        },
+    );
+    assert!(view.is_none());
+}
+
+#[test]
+fn confirm_tells_unknown_from_unrequested() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    // An unknown id wins over any parameter error.
+    let mut p = confirm(1, 100, 1);
+    p.token_id = String::new();
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &p),
+        Err(ContractError::InvalidMilestone)
+    );
+    assert_eq!(
+        env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1)),
+        Err(ContractError::NotRequested)
+    );
+}
+
+// ---- Access control ------------------------------------------------------------
+
+#[test]
+fn contract_senders_are_unauthorized() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+    // Any existing contract will do as the sender.
+    let other = env.init(&init_params(vec![100])).unwrap();
+    let sender = Address::Contract(other);
+
+    assert_eq!(
+        env.update_as(contract, ORACLE, sender, "requestRelease", &request(0)),
+        Err(ContractError::Unauthorized)
+    );
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    assert_eq!(
+        env.update_as(
+            contract,
+            CLIENT,
+            sender,
+            "confirmPayment",
+            &confirm(0, 100, 1)
+        ),
+        Err(ContractError::Unauthorized)
+    );
+    env.update(contract, CLIENT, "confirmPayment", &confirm(0, 100, 1))
+        .unwrap();
+}
+
+#[test]
+fn accounts_outside_the_role_are_unauthorized() {
+    let mut env = TestEnv::new();
+    let contract = env.init(&init_params(vec![100])).unwrap();
+
+    assert_eq!(
+        env.update(contract, FREELANCER, "requestRelease", &request(0)),
+        Err(ContractError::Unauthorized)
+    );
+    env.update(contract, ORACLE, "requestRelease", &request(0))
+        .unwrap();
+    assert_eq!(
+        env.update(contract, ORACLE, "confirmPayment", &confirm(0, 100, 1)),
+        Err(ContractError::Unauthorized)
+    );
+}